@@ -0,0 +1,153 @@
+//! What a day works out for each part, kept as the kind of value it is so that the runner can
+//! show, compare and serialize it properly.
+
+use crate::error::Error;
+use std::{any::type_name, convert::TryInto, fmt};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Answer {
+    Integer(i128),
+    Text(String),
+    /// A picture, one row to a line.
+    Image(String),
+    /// There's no answer, for the reason given.
+    None(String),
+    /// An answer along with more about how it was worked out, which isn't part of the answer.
+    Detailed(Box<Answer>, String),
+}
+
+impl Answer {
+    /// An integer answer from any integer type, provided it fits in an `i128`.
+    pub(crate) fn integer<T: TryInto<i128> + fmt::Display + Copy>(value: T) -> Result<Self, Error> {
+        value.try_into().map(Self::Integer).map_err(|_| {
+            Error::Overflow(format!(
+                "the answer {} is too big to report (it's a {})",
+                value,
+                type_name::<T>()
+            ))
+        })
+    }
+    /// A picture drawn a row to a line, without the trailing newline.
+    pub(crate) fn image(rows: &str) -> Self {
+        Self::Image(rows.trim_end_matches('\n').to_string())
+    }
+    pub(crate) fn with_detail(self, detail: String) -> Self {
+        Self::Detailed(Box::new(self), detail)
+    }
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Self::Integer(_) => "integer",
+            Self::Text(_) => "text",
+            Self::Image(_) => "image",
+            Self::None(_) => "none",
+            Self::Detailed(answer, _) => answer.kind(),
+        }
+    }
+    /// The answer without any detail.
+    pub(crate) fn plain(&self) -> &Self {
+        match self {
+            Self::Detailed(answer, _) => answer.plain(),
+            answer => answer,
+        }
+    }
+    pub(crate) fn detail(&self) -> Option<&str> {
+        match self {
+            Self::Detailed(_, detail) => Some(detail),
+            _ => None,
+        }
+    }
+    /// The answer followed by its detail: on the same line if that's one line long, or on
+    /// lines of its own if not.
+    pub(crate) fn described(&self) -> String {
+        match self.detail() {
+            Some(detail) if detail.contains('\n') => format!("{}\n{}", self, detail),
+            Some(detail) => format!("{} {}", self, detail),
+            None => self.to_string(),
+        }
+    }
+    /// Whether the answer is the same as `expected`, written the way `Display` writes it, or
+    /// along with its detail the way `described` does. Integers compare as numbers, and blank
+    /// lines around pictures and whitespace around everything else don't count.
+    pub(crate) fn matches(&self, expected: &str) -> bool {
+        match self {
+            Self::Integer(n) => expected.trim().parse::<i128>() == Ok(*n),
+            Self::Image(rows) => rows.trim_matches('\n') == expected.trim_matches('\n'),
+            Self::Text(_) | Self::None(_) => self.to_string().trim() == expected.trim(),
+            Self::Detailed(answer, _) => {
+                answer.matches(expected) || self.described().trim() == expected.trim()
+            }
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Integer(n) => write!(f, "{}", n),
+            Self::Text(text) | Self::Image(text) => write!(f, "{}", text),
+            Self::None(reason) => write!(f, "no answer: {}", reason),
+            Self::Detailed(answer, _) => write!(f, "{}", answer),
+        }
+    }
+}
+
+macro_rules! integers {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Self::Integer(i128::from(n))
+                }
+            }
+        )*
+    };
+}
+
+integers!(u8, u16, u32, u64, i8, i16, i32, i64, i128);
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Self::Integer(n as i128)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn answers() {
+        assert_eq!(Answer::from(42u8), Answer::Integer(42));
+        assert_eq!(Answer::integer(u128::MAX / 4).unwrap().kind(), "integer");
+        assert_eq!(
+            Answer::integer(u128::MAX).unwrap_err().to_string(),
+            format!(
+                "Overflow: the answer {} is too big to report (it's a u128)",
+                u128::MAX
+            )
+        );
+        assert!(Answer::Integer(42).matches(" 042\n"));
+        assert!(!Answer::Integer(42).matches("42 at (1, 0)"));
+        assert!(Answer::image("#.\n.#\n").matches("\n#.\n.#\n"));
+        assert!(!Answer::image("#.\n.#").matches("#.\n.#."));
+        let none = Answer::None("nothing crosses".to_string());
+        assert_eq!(none.to_string(), "no answer: nothing crosses");
+        assert!(none.matches("no answer: nothing crosses\n"));
+        assert!(!Answer::Text(String::new()).matches("no answer: nothing crosses"));
+        let detailed = Answer::Integer(6).with_detail("at (3, 3)".to_string());
+        assert_eq!(
+            (detailed.to_string().as_str(), detailed.kind()),
+            ("6", "integer")
+        );
+        assert!(detailed.matches("6"));
+        assert!(detailed.matches("6 at (3, 3)"));
+        assert!(!detailed.matches("6 at (3, 4)"));
+        let table = Answer::Integer(2).with_detail("a  b\n1  1".to_string());
+        assert_eq!(table.described(), "2\na  b\n1  1");
+    }
+}
@@ -0,0 +1,194 @@
+use std::ops::{Div, Mul, Rem, Sub};
+
+/// The unsigned integer types, for arithmetic that works the same on all of them.
+pub(crate) trait Unsigned:
+    Copy
+    + Ord
+    + Div<Output = Self>
+    + Mul<Output = Self>
+    + Rem<Output = Self>
+    + Sub<Output = Self>
+    + From<u8>
+{
+    /// The lowest eight bits, for digits that are already known to fit.
+    fn low_byte(self) -> u8;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl Unsigned for $t {
+                fn low_byte(self) -> u8 {
+                    self as u8
+                }
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+unsigned!(u8, u16, u32, u64, u128, usize);
+
+/// The digits of a number, most significant first, worked out as they're asked for.
+#[derive(Clone, Debug)]
+pub(crate) struct Digits<T> {
+    /// The digits that haven't been taken from either end yet.
+    number: T,
+    radix: T,
+    /// The place value of the first remaining digit.
+    high: T,
+    len: usize,
+}
+
+impl<T: Unsigned> Digits<T> {
+    /// Zero has a single digit, like everything else below `radix`.
+    pub(crate) fn new(number: T, radix: u8) -> Self {
+        assert!(radix >= 2, "Digits need a radix of at least 2");
+        let radix = T::from(radix);
+        let (mut high, mut len) = (T::from(1), 1);
+        while number / high >= radix {
+            high = high * radix;
+            len += 1;
+        }
+        Self {
+            number,
+            radix,
+            high,
+            len,
+        }
+    }
+    /// Every run of `N` neighbouring digits, in order.
+    pub(crate) fn windows<const N: usize>(self) -> impl Iterator<Item = [u8; N]> {
+        let mut window = [0; N];
+        self.enumerate().filter_map(move |(i, digit)| {
+            if N == 0 {
+                return None;
+            }
+            window.rotate_left(1);
+            window[N - 1] = digit;
+            (i + 1 >= N).then_some(window)
+        })
+    }
+    /// Each run of equal digits as the digit and how many times it repeats.
+    pub(crate) fn runs(self) -> impl Iterator<Item = (u8, usize)> {
+        let mut digits = self.peekable();
+        std::iter::from_fn(move || {
+            let digit = digits.next()?;
+            let mut len = 1;
+            while digits.next_if_eq(&digit).is_some() {
+                len += 1;
+            }
+            Some((digit, len))
+        })
+    }
+}
+
+impl<T: Unsigned> Iterator for Digits<T> {
+    type Item = u8;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let digit = self.number / self.high;
+        self.number = self.number % self.high;
+        self.high = self.high / self.radix;
+        self.len -= 1;
+        Some(digit.low_byte())
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T: Unsigned> DoubleEndedIterator for Digits<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let digit = self.number % self.radix;
+        self.number = self.number / self.radix;
+        self.high = self.high / self.radix;
+        self.len -= 1;
+        Some(digit.low_byte())
+    }
+}
+
+impl<T: Unsigned> ExactSizeIterator for Digits<T> {}
+
+pub(crate) trait DigitsExt: Unsigned {
+    /// The decimal digits, most significant first.
+    fn digits(self) -> Digits<Self> {
+        Digits::new(self, 10)
+    }
+}
+
+impl<T: Unsigned> DigitsExt for T {}
+
+/// Puts digits, most significant first, back together into a number, or `None` if it doesn't
+/// fit in a `T` or a digit is too big for `radix`.
+pub(crate) fn from_digits<T: Unsigned>(
+    digits: impl IntoIterator<Item = u8>,
+    radix: u8,
+) -> Option<T> {
+    digits.into_iter().try_fold(T::from(0), |number, digit| {
+        if digit >= radix {
+            return None;
+        }
+        number
+            .checked_mul(T::from(radix))?
+            .checked_add(T::from(digit))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn digits() {
+        assert_eq!(1203u32.digits().collect::<Vec<_>>(), vec![1, 2, 0, 3]);
+        assert_eq!(1203u32.digits().rev().collect::<Vec<_>>(), vec![3, 0, 2, 1]);
+        assert_eq!(0u8.digits().collect::<Vec<_>>(), vec![0]);
+        assert_eq!(u64::MAX.digits().len(), 20);
+        assert_eq!(u128::MAX.digits().next_back(), Some(5));
+        assert_eq!(Digits::new(255u8, 2).len(), 8);
+        assert_eq!(
+            Digits::new(0xbeefu16, 16).collect::<Vec<_>>(),
+            vec![11, 14, 14, 15]
+        );
+        let mut digits = 12345u32.digits();
+        assert_eq!(
+            (
+                digits.next(),
+                digits.next_back(),
+                digits.next_back(),
+                digits.next()
+            ),
+            (Some(1), Some(5), Some(4), Some(2))
+        );
+        assert_eq!(digits.collect::<Vec<_>>(), vec![3]);
+    }
+    #[test]
+    fn helpers() {
+        assert_eq!(
+            1123u32.digits().windows().collect::<Vec<_>>(),
+            vec![[1, 1], [1, 2], [2, 3]]
+        );
+        assert_eq!(7u32.digits().windows::<2>().count(), 0);
+        assert_eq!(
+            1112333u32.digits().runs().collect::<Vec<_>>(),
+            vec![(1, 3), (2, 1), (3, 3)]
+        );
+        assert_eq!(from_digits::<u32>(1203u32.digits(), 10), Some(1203));
+        assert_eq!(from_digits::<u8>(vec![2, 5, 6], 10), None);
+        assert_eq!(from_digits::<u8>(vec![1, 2], 2), None);
+        let max = Digits::new(u128::MAX, 7);
+        assert_eq!(from_digits::<u128>(max, 7), Some(u128::MAX));
+    }
+}
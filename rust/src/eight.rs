@@ -1,85 +1,245 @@
-use std::convert::TryFrom;
+use crate::{
+    answer::Answer,
+    error::{self, Error, ParseError},
+    grid::Grid,
+    ocr,
+    params::Params,
+    Part,
+};
+use blend::Blend;
+use palette::Palette;
+
+mod blend;
+mod encode;
+mod export;
+mod palette;
+mod render;
 
 const WIDTH: usize = 25;
 const HEIGHT: usize = 6;
 
-pub(crate) fn run(input: String) -> [String; 2] {
-    let pixels = input
-        .lines()
-        .next()
-        .unwrap()
-        .chars()
-        .map(|x| x.to_digit(10).unwrap())
-        .collect::<Vec<_>>();
-    let part1 = part1(&pixels);
-    let part2 = part2(&pixels);
-    [part1, part2]
-}
-
-fn part1(pixels: &[u32]) -> String {
-    let layer = pixels
-        .chunks(WIDTH * HEIGHT)
-        .min_by_key(|x| x.iter().filter(|i| **i == 0).count())
-        .unwrap();
-    let ones = layer.iter().filter(|i| **i == 1).count();
-    let twos = layer.iter().filter(|i| **i == 2).count();
-    format!("{}", ones * twos)
+/// A picture in the Space Image Format: layers of `width` by `height` pixel digits, one row
+/// after another, with the first layer on top.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct SpaceImage {
+    width: usize,
+    height: usize,
+    digits: Vec<u8>,
+}
+
+impl SpaceImage {
+    /// Reads the digits on the first line of `input` as layers of the given size.
+    fn parse(input: &str, width: usize, height: usize) -> Result<Self, Error> {
+        if width == 0 || height == 0 {
+            return Err(Error::Unsupported(format!(
+                "A {}x{} image has no pixels",
+                width, height
+            )));
+        }
+        let line = error::first_line(input, "a string of digits")?.trim_end();
+        let digits = line
+            .chars()
+            .enumerate()
+            .map(|(i, c)| match c.to_digit(10) {
+                Some(digit) => Ok(digit as u8),
+                None => Err(ParseError::new(1, i + 1, &c.to_string(), "a digit")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let size = width.checked_mul(height).ok_or_else(|| {
+            Error::Unsupported(format!("A {}x{} image is too big", width, height))
+        })?;
+        let whole = digits.len() - digits.len() % size;
+        if whole < digits.len() {
+            let expected = format!("whole {}x{} layers of {} digits", width, height, size);
+            let rest = line.chars().skip(whole).collect::<String>();
+            return Err(ParseError::new(1, whole + 1, &rest, &expected).into());
+        }
+        Ok(Self {
+            width,
+            height,
+            digits,
+        })
+    }
+    /// Each layer's digits, top layer first.
+    fn layers(&self) -> std::slice::ChunksExact<'_, u8> {
+        self.digits.chunks_exact(self.width * self.height)
+    }
+    /// How many of each digit there are on each layer, top layer first.
+    fn histograms(&self) -> impl Iterator<Item = [usize; 10]> + '_ {
+        self.layers().map(|layer| {
+            let mut histogram = [0; 10];
+            for &digit in layer {
+                histogram[usize::from(digit)] += 1;
+            }
+            histogram
+        })
+    }
+    /// Checks that every digit has a color in `palette`.
+    fn check(&self, palette: &Palette) -> Result<(), ParseError> {
+        match self.digits.iter().position(|&d| palette.get(d).is_none()) {
+            Some(i) => Err(ParseError::new(
+                1,
+                i + 1,
+                &self.digits[i].to_string(),
+                &palette.expected(),
+            )),
+            None => Ok(()),
+        }
+    }
+    fn image(&self, digits: &[Option<u8>], palette: &Palette) -> Image {
+        let pixels = digits.iter().map(|digit| match digit {
+            Some(digit) => palette.get(*digit).expect("Checked against the palette"),
+            None => Pixel::Transparent,
+        });
+        Image {
+            pixels: Grid::from_cells(self.width, pixels.collect()).expect("Layers are whole"),
+        }
+    }
+    /// Each layer on its own, top layer first.
+    fn layer_images(&self, decoding: &Decoding) -> Result<Vec<Image>, ParseError> {
+        self.check(&decoding.palette)?;
+        let layers = self.layers().map(|layer| {
+            let digits = layer.iter().copied().map(Some).collect::<Vec<_>>();
+            self.image(&digits, &decoding.palette)
+        });
+        Ok(layers.collect())
+    }
+    /// The picture after blending in each layer in turn under the ones above it, from the
+    /// top layer alone to the finished picture.
+    fn steps(&self, decoding: &Decoding) -> Result<Vec<Image>, ParseError> {
+        self.check(&decoding.palette)?;
+        let Decoding { palette, blend } = decoding;
+        // The digit showing at each pixel so far.
+        let mut shown = vec![None; self.width * self.height];
+        let mut steps = Vec::new();
+        for layer in self.layers() {
+            for (shown, &digit) in shown.iter_mut().zip(layer) {
+                let opaque = palette.get(digit) != Some(Pixel::Transparent);
+                if opaque && blend.wins(digit, *shown) {
+                    *shown = Some(digit);
+                }
+            }
+            steps.push(self.image(&shown, palette));
+        }
+        Ok(steps)
+    }
+    /// Blends the layers into the finished picture.
+    fn composite(&self, decoding: &Decoding) -> Result<Image, ParseError> {
+        Ok(self
+            .steps(decoding)?
+            .pop()
+            .expect("An image has at least one layer"))
+    }
+}
+
+impl std::fmt::Display for SpaceImage {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for digit in &self.digits {
+            write!(f, "{}", digit)?;
+        }
+        Ok(())
+    }
+}
+
+/// How to turn an image's digits into a picture: the color of each digit, set with
+/// `palette=0:black,1:white,2:transparent`, and which layer's digit shows at each pixel, set
+/// with `blend=first`, `blend=last` or `blend=priority:1,0`.
+#[derive(Clone, Debug, Default)]
+struct Decoding {
+    palette: Palette,
+    blend: Blend,
+}
+
+impl Decoding {
+    fn from_params(params: &Params) -> Result<Self, Error> {
+        let palette = match params.get::<String>("palette")? {
+            Some(palette) => palette
+                .parse()
+                .map_err(|err| Error::Unsupported(format!("Invalid palette: {}", err)))?,
+            None => Palette::default(),
+        };
+        let blend = match params.get::<String>("blend")? {
+            Some(blend) => blend
+                .parse()
+                .map_err(|err| Error::Unsupported(format!("Invalid blend: {}", err)))?,
+            None => Blend::default(),
+        };
+        Ok(Self { palette, blend })
+    }
+}
+
+pub(crate) fn run(input: &str, part: Part, params: &Params) -> Result<Answer, Error> {
+    let width = params.get_or("width", WIDTH)?;
+    let height = params.get_or("height", HEIGHT)?;
+    let image = SpaceImage::parse(input, width, height)?;
+    match part {
+        Part::One => Ok(part1(&image)),
+        Part::Two => {
+            let picture = params.get_or("picture", false)?;
+            let style = match params.get::<String>("render")? {
+                Some(style) => style.parse().map_err(Error::Unsupported)?,
+                None => render::Style::Ascii,
+            };
+            let decoding = Decoding::from_params(params)?;
+            part2(&image, &decoding, picture.then_some(style))
+        }
+    }
+}
+
+fn part1(image: &SpaceImage) -> Answer {
+    let histogram = image
+        .histograms()
+        .min_by_key(|histogram| histogram[0])
+        .expect("An image has at least one layer");
+    (histogram[1] * histogram[2]).into()
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum Pixel {
     Transparent,
-    White,
-    Black,
+    Rgb([u8; 3]),
 }
 
 impl Pixel {
-    fn is_opaque(self) -> bool {
-        !self.is_transparent()
+    const BLACK: Self = Self::Rgb([0, 0, 0]);
+    const WHITE: Self = Self::Rgb([255, 255, 255]);
+    /// How bright the pixel is, from 0 to 255, with transparent pixels as dark as black.
+    fn luma(self) -> u8 {
+        match self {
+            Self::Transparent => 0,
+            // The Rec. 601 weights, scaled to add up to 256.
+            Self::Rgb([r, g, b]) => {
+                let luma = 77 * u32::from(r) + 150 * u32::from(g) + 29 * u32::from(b);
+                (luma >> 8) as u8
+            }
+        }
     }
-    fn is_transparent(self) -> bool {
-        self == Self::Transparent
+    /// Whether the pixel is bright enough to count as ink, like white in the puzzle.
+    fn is_lit(self) -> bool {
+        self.luma() >= 128
     }
 }
 
 impl std::fmt::Display for Pixel {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Self::Transparent => " ",
-                Self::Black => "◼️",
-                Self::White => "◻️",
-            }
-        )
+        let c = match self {
+            Self::Transparent => ' ',
+            pixel if pixel.is_lit() => '#',
+            _ => '.',
+        };
+        write!(f, "{}", c)
     }
 }
 
-impl TryFrom<u32> for Pixel {
-    type Error = ();
-    fn try_from(x: u32) -> Result<Self, Self::Error> {
-        match x {
-            0 => Ok(Self::Black),
-            1 => Ok(Self::White),
-            2 => Ok(Self::Transparent),
-            _ => Err(()),
-        }
-    }
-}
-
-#[derive(Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 struct Image {
-    pixels: Vec<Pixel>,
-    width: usize,
+    pixels: Grid<Pixel>,
 }
 
 impl std::fmt::Display for Image {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        dbg!(self.pixels.len());
-        for row in 0..(self.pixels.len() / self.width) {
-            for col in 0..self.width {
-                let pixel = self[(row, col)];
+        for row in self.pixels.rows() {
+            for pixel in row {
                 write!(f, "{}", pixel)?;
             }
             writeln!(f)?;
@@ -89,43 +249,138 @@ impl std::fmt::Display for Image {
 }
 
 impl Image {
-    fn new(width: usize, height: usize) -> Self {
-        Self {
-            pixels: vec![Pixel::Transparent; width * height],
-            width,
-        }
+    /// Which pixels are lit, the color the letters are drawn in.
+    fn lit(&self) -> Grid<bool> {
+        let cells = self.pixels.cells().iter().map(|&p| p.is_lit());
+        Grid::from_cells(self.pixels.width(), cells.collect()).expect("Same shape")
     }
 }
 
-impl std::ops::Index<(usize, usize)> for Image {
-    type Output = Pixel;
-    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
-        &self.pixels[row * self.width + col]
+/// Writes the picture as a `pbm`, `pgm`, `ppm` or `png` file, taking `width` and `height` as
+/// `run` does. `scale=N` draws each pixel N pixels across, and `frames=layers` or
+/// `frames=steps` writes each layer, or the picture after each layer is stacked, as a frame
+/// of its own.
+///
+/// `sif` goes the other way, turning text art like the `ascii` renderer's into a new input.
+pub(crate) fn draw(input: &str, format: &str, params: &Params) -> Result<Vec<u8>, Error> {
+    if format == "sif" {
+        return sif(input, params);
+    }
+    let format = export::Format::from_name(format).ok_or_else(|| {
+        Error::Unsupported(format!(
+            "Day 8 can't be drawn as {} (try pbm, pgm, ppm, png or sif)",
+            format
+        ))
+    })?;
+    let image = SpaceImage::parse(
+        input,
+        params.get_or("width", WIDTH)?,
+        params.get_or("height", HEIGHT)?,
+    )?;
+    let decoding = Decoding::from_params(params)?;
+    let frames = match params.choice("frames", &["final", "layers", "steps"])? {
+        Some("layers") => image.layer_images(&decoding)?,
+        Some("steps") => image.steps(&decoding)?,
+        _ => vec![image.composite(&decoding)?],
+    };
+    match params.get_or("scale", 1)? {
+        0 => Err(Error::Unsupported("The scale has to be at least 1".to_string())),
+        scale => export::write(&frames, format, scale),
     }
 }
 
-impl std::ops::IndexMut<(usize, usize)> for Image {
-    fn index_mut(
-        &mut self,
-        (row, col): (usize, usize),
-    ) -> &mut <Self as std::ops::Index<(usize, usize)>>::Output {
-        &mut self.pixels[row * self.width + col]
+/// Hides the text art in `input` in `layers=N` layers (100 by default) of noise picked with
+/// `seed=N`, as a line of digits that `run` reads with the art's width and height. The layers
+/// only stack back up into the art with the puzzle's `blend=first`.
+fn sif(input: &str, params: &Params) -> Result<Vec<u8>, Error> {
+    let image = encode::art(input)?;
+    let decoding = Decoding::from_params(params)?;
+    if decoding.blend != Blend::FirstOpaque {
+        return Err(Error::Unsupported(
+            "Pictures can only be encoded for blend=first".to_string(),
+        ));
     }
+    let mut rng = encode::Rng::new(params.get_or("seed", 0)?);
+    let layers = params.get_or("layers", 100)?;
+    let encoded = encode::encode(&image, layers, &decoding.palette, &mut rng)?;
+    Ok(format!("{}\n", encoded).into_bytes())
 }
 
-fn part2(pixels: &[u32]) -> String {
-    let mut image = Image::new(WIDTH, HEIGHT);
-    for layer in pixels.chunks(WIDTH * HEIGHT) {
-        for col in 0..WIDTH {
-            for row in 0..HEIGHT {
-                let spec = Pixel::try_from(layer[row * WIDTH + col]).expect("Invalid color");
-                if spec.is_opaque() {
-                    if image[(row, col)].is_transparent() {
-                        image[(row, col)] = spec;
-                    }
-                }
-            }
+/// Reads the letters in the picture, or shows the picture itself in the given style.
+fn part2(
+    image: &SpaceImage,
+    decoding: &Decoding,
+    picture: Option<render::Style>,
+) -> Result<Answer, Error> {
+    let image = image.composite(decoding)?;
+    if let Some(style) = picture {
+        return Ok(Answer::image(&render::render(&image, style)));
+    }
+    let reading = ocr::read(&image.lit())?;
+    match reading.unknown.len() {
+        0 if reading.text.is_empty() => Ok(Answer::None("the picture is blank".to_string())),
+        0 => Ok(Answer::Text(reading.text)),
+        _ => {
+            let columns = reading.unknown.iter().map(|col| format!("column {}", col + 1));
+            Err(Error::Unsupported(format!(
+                "Couldn't read all of {:?}: the letters at {} aren't in the font \
+                 (try -p picture=true)",
+                reading.text,
+                columns.collect::<Vec<_>>().join(", ")
+            )))
         }
     }
-    format!("\n{}", image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parsing() {
+        let image = SpaceImage::parse("0222112222120000", 2, 2).unwrap();
+        assert_eq!(image.layers().len(), 4);
+        assert_eq!(image.layers().nth(1), Some(&[1, 1, 2, 2][..]));
+        assert_eq!(
+            SpaceImage::parse("022211222", 2, 2).unwrap_err().to_string(),
+            "Invalid input: line 1, column 9: expected whole 2x2 layers of 4 digits, found \"2\""
+        );
+        assert_eq!(
+            SpaceImage::parse("02x2", 2, 2).unwrap_err().to_string(),
+            "Invalid input: line 1, column 3: expected a digit, found \"x\""
+        );
+        let image = SpaceImage::parse("22221203", 2, 2).unwrap();
+        assert_eq!(
+            image.composite(&Decoding::default()).unwrap_err(),
+            ParseError::new(1, 8, "3", "a color (0, 1 or 2)")
+        );
+        assert!(SpaceImage::parse("0122", 0, 2).is_err());
+        assert!(SpaceImage::parse("0122", usize::MAX, 2).is_err());
+        let params = "width=5".parse::<Params>().unwrap();
+        assert_eq!(
+            run(&"0".repeat(30), Part::Two, &params).unwrap(),
+            Answer::None("the picture is blank".to_string())
+        );
+        let params = "width=abc".parse::<Params>().unwrap();
+        assert_eq!(
+            run("0122", Part::One, &params).unwrap_err().to_string(),
+            "Invalid width \"abc\""
+        );
+        let params = "width=2 height=-1".parse::<Params>().unwrap();
+        assert_eq!(
+            draw("0122", "pbm", &params).unwrap_err().to_string(),
+            "Invalid height \"-1\""
+        );
+    }    #[test]
+    fn encoding() {
+        let art = "#.#\n.#.";
+        let params = "width=3 height=2 layers=5".parse::<Params>().unwrap();
+        let encoded = String::from_utf8(draw(art, "sif", &params).unwrap()).unwrap();
+        let image = SpaceImage::parse(&encoded, 3, 2).unwrap();
+        assert_eq!(image.composite(&Decoding::default()).unwrap().to_string(), "#.#\n.#.\n");
+        let params = "blend=last".parse::<Params>().unwrap();
+        assert_eq!(
+            draw(art, "sif", &params).unwrap_err().to_string(),
+            "Pictures can only be encoded for blend=first"
+        );
+    }
 }
@@ -0,0 +1,81 @@
+use std::str::FromStr;
+
+/// How to pick which layer's digit shows at a pixel. Transparent digits never show.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(super) enum Blend {
+    /// The top layer that isn't transparent there, as in the puzzle.
+    #[default]
+    FirstOpaque,
+    /// The bottom layer that isn't transparent there, as if the layers were painted top
+    /// first.
+    LastWins,
+    /// The digit that comes first in this list, whichever layer it's on. Digits that aren't
+    /// listed lose to those that are, and otherwise the top layer wins.
+    Priority(Vec<u8>),
+}
+
+impl Blend {
+    fn rank(&self, digit: u8) -> usize {
+        match self {
+            Self::Priority(order) => order
+                .iter()
+                .position(|&d| d == digit)
+                .unwrap_or(order.len()),
+            _ => 0,
+        }
+    }
+    /// Whether an opaque `digit` on the next layer down replaces `shown`, the digit showing
+    /// from the layers above, if any.
+    pub(super) fn wins(&self, digit: u8, shown: Option<u8>) -> bool {
+        match (self, shown) {
+            (_, None) | (Self::LastWins, _) => true,
+            (Self::FirstOpaque, Some(_)) => false,
+            (Self::Priority(_), Some(shown)) => self.rank(digit) < self.rank(shown),
+        }
+    }
+}
+
+impl FromStr for Blend {
+    type Err = String;
+    /// Parses `first`, `last` or `priority:` followed by comma-separated digits.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "first" => Ok(Self::FirstOpaque),
+            "last" => Ok(Self::LastWins),
+            _ => match s.strip_prefix("priority:") {
+                Some(digits) => digits
+                    .split(',')
+                    .map(|digit| match digit.parse::<u8>() {
+                        Ok(digit) if digit < 10 => Ok(digit),
+                        _ => Err(format!("expected a digit, found {:?}", digit)),
+                    })
+                    .collect::<Result<_, _>>()
+                    .map(Self::Priority),
+                None => Err(format!(
+                    "expected first, last or priority:<digits>, found {:?}",
+                    s
+                )),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn blends() {
+        assert!(Blend::FirstOpaque.wins(1, None));
+        assert!(!Blend::FirstOpaque.wins(1, Some(0)));
+        assert!(Blend::LastWins.wins(1, Some(0)));
+        let priority = "priority:1,0".parse::<Blend>().unwrap();
+        assert_eq!(priority, Blend::Priority(vec![1, 0]));
+        assert!(priority.wins(1, Some(0)));
+        assert!(!priority.wins(0, Some(1)));
+        assert!(priority.wins(0, Some(3)));
+        assert!(!priority.wins(4, Some(3)));
+        assert_eq!("first".parse(), Ok(Blend::FirstOpaque));
+        assert!("priority:1,x".parse::<Blend>().is_err());
+        assert!("middle".parse::<Blend>().is_err());
+    }
+}
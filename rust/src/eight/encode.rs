@@ -0,0 +1,149 @@
+use super::{palette::Palette, Image, Pixel, SpaceImage};
+use crate::{error::Error, error::ParseError, grid::Grid};
+
+/// A small random number generator (SplitMix64), so that the same seed always encodes a
+/// picture the same way.
+pub(super) struct Rng(u64);
+
+impl Rng {
+    pub(super) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+    pub(super) fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+    /// A number from 0 up to but not including `n`, which mustn't be 0.
+    pub(super) fn below(&mut self, n: usize) -> usize {
+        // The bias is far too small to matter for test inputs.
+        (self.next() % n as u64) as usize
+    }
+}
+
+/// Reads a picture drawn the way the `ascii` renderer draws them: `#` for white, `.` for
+/// black and a space for transparent. Short rows are padded out with transparent pixels.
+pub(super) fn art(text: &str) -> Result<Image, ParseError> {
+    let rows = text.lines().collect::<Vec<_>>();
+    let width = rows.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+    if width == 0 {
+        return Err(ParseError::new(1, 1, "", "a picture"));
+    }
+    let mut cells = Vec::with_capacity(width * rows.len());
+    for (line, row) in rows.iter().enumerate() {
+        for (col, c) in row.chars().enumerate() {
+            cells.push(match c {
+                '#' => Pixel::WHITE,
+                '.' => Pixel::BLACK,
+                ' ' => Pixel::Transparent,
+                _ => {
+                    let expected = "#, . or a space";
+                    return Err(ParseError::new(line + 1, col + 1, &c.to_string(), expected));
+                }
+            });
+        }
+        cells.resize(width * (line + 1), Pixel::Transparent);
+    }
+    Ok(Image {
+        pixels: Grid::from_cells(width, cells).expect("Rows are padded"),
+    })
+}
+
+/// Hides `image` in `layers` layers of digits from `palette`. Each pixel's color goes on a
+/// random layer, with transparent digits above it and random noise below, so no one layer
+/// shows the picture but stacking them with the puzzle's rule does. Transparent pixels stay
+/// transparent all the way down, or the noise would show through.
+pub(super) fn encode(
+    image: &Image,
+    layers: usize,
+    palette: &Palette,
+    rng: &mut Rng,
+) -> Result<SpaceImage, Error> {
+    let transparent = palette.digit(Pixel::Transparent);
+    if layers == 0 {
+        return Err(Error::Unsupported("A picture needs at least one layer".to_string()));
+    }
+    if layers > 1 && transparent.is_none() {
+        return Err(Error::Unsupported(format!(
+            "Can't hide a picture in {} layers without a transparent digit in the palette",
+            layers
+        )));
+    }
+    let digits = image
+        .pixels
+        .cells()
+        .iter()
+        .map(|&pixel| {
+            palette.digit(pixel).ok_or_else(|| {
+                Error::Unsupported(format!("The palette has no digit for {:?}", pixel))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let noise = palette.digits().collect::<Vec<_>>();
+    let size = digits.len();
+    let mut layered = vec![0; size * layers];
+    for (i, &digit) in digits.iter().enumerate() {
+        let depth = rng.below(layers);
+        for layer in 0..layers {
+            layered[layer * size + i] = if layer == depth {
+                digit
+            } else if layer < depth || Some(digit) == transparent {
+                // There's always a transparent digit when there's more than one layer.
+                transparent.expect("Checked above")
+            } else {
+                noise[rng.below(noise.len())]
+            };
+        }
+    }
+    Ok(SpaceImage {
+        width: image.pixels.width(),
+        height: image.pixels.height(),
+        digits: layered,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Decoding;
+    use super::*;
+    #[test]
+    fn pictures() {
+        let image = art("#.\n #\n").unwrap();
+        assert_eq!(image.to_string(), "#.\n #\n");
+        assert_eq!(art("#.\n#").unwrap().to_string(), "#.\n# \n");
+        assert_eq!(art("#x").unwrap_err(), ParseError::new(1, 2, "x", "#, . or a space"));
+        assert!(art("").is_err());
+    }
+    #[test]
+    fn round_trip() {
+        let mut rng = Rng::new(2019);
+        for _ in 0..200 {
+            let (width, height) = (1 + rng.below(12), 1 + rng.below(8));
+            let colors = [Pixel::BLACK, Pixel::WHITE, Pixel::Transparent];
+            let cells = (0..width * height).map(|_| colors[rng.below(3)]).collect();
+            let image = Image {
+                pixels: Grid::from_cells(width, cells).unwrap(),
+            };
+            let layers = 1 + rng.below(20);
+            let seed = rng.next();
+            let encoded = encode(&image, layers, &Palette::default(), &mut Rng::new(seed)).unwrap();
+            let again = encode(&image, layers, &Palette::default(), &mut Rng::new(seed)).unwrap();
+            assert_eq!(encoded, again);
+            let decoded = SpaceImage::parse(&encoded.to_string(), width, height).unwrap();
+            assert_eq!(decoded.layers().len(), layers);
+            assert_eq!(decoded.composite(&Decoding::default()).unwrap(), image);
+        }
+    }
+    #[test]
+    fn palettes() {
+        let image = art("#.").unwrap();
+        let palette = "0:black,1:white".parse::<Palette>().unwrap();
+        assert!(encode(&image, 1, &palette, &mut Rng::new(0)).is_ok());
+        assert!(encode(&image, 0, &palette, &mut Rng::new(0)).is_err());
+        assert!(encode(&image, 2, &palette, &mut Rng::new(0)).is_err());
+        let palette = "5:black,7:transparent".parse::<Palette>().unwrap();
+        assert!(encode(&image, 2, &palette, &mut Rng::new(0)).is_err());
+    }
+}
@@ -0,0 +1,163 @@
+use super::{Image, Pixel};
+use crate::error::Error;
+
+/// The image file formats that `write` knows.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum Format {
+    /// Black and white, split by brightness. Transparent pixels come out white.
+    Pbm,
+    /// Grayscale, with transparent pixels in mid gray.
+    Pgm,
+    /// Color, with transparent pixels in mid gray.
+    Ppm,
+    /// Color with an alpha channel. More than one frame makes an animated PNG.
+    Png,
+}
+
+impl Format {
+    pub(super) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pbm" => Some(Self::Pbm),
+            "pgm" => Some(Self::Pgm),
+            "ppm" => Some(Self::Ppm),
+            "png" => Some(Self::Png),
+            _ => None,
+        }
+    }
+}
+
+impl Pixel {
+    fn rgba(self) -> [u8; 4] {
+        match self {
+            Self::Rgb([r, g, b]) => [r, g, b, 255],
+            Self::Transparent => [128, 128, 128, 0],
+        }
+    }
+}
+
+/// The rows of `image` with every pixel blown up into a `scale` by `scale` square.
+fn scaled(image: &Image, scale: usize) -> impl Iterator<Item = Vec<Pixel>> + '_ {
+    image.pixels.rows().flat_map(move |row| {
+        let row = row
+            .iter()
+            .flat_map(|&pixel| std::iter::repeat_n(pixel, scale))
+            .collect::<Vec<_>>();
+        std::iter::repeat_n(row, scale)
+    })
+}
+
+/// Writes a binary Netpbm image for each frame, one after another, which is how Netpbm
+/// stores a sequence.
+fn netpbm(frames: &[Image], format: Format, scale: usize) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for frame in frames {
+        let (width, height) = (frame.pixels.width() * scale, frame.pixels.height() * scale);
+        let magic = match format {
+            Format::Pbm => "P4",
+            Format::Pgm => "P5",
+            _ => "P6",
+        };
+        bytes.extend(format!("{}\n{} {}\n", magic, width, height).bytes());
+        if format != Format::Pbm {
+            bytes.extend(b"255\n");
+        }
+        for row in scaled(frame, scale) {
+            match format {
+                // Eight pixels to a byte, most significant bit first, with 1 for black.
+                Format::Pbm => bytes.extend(row.chunks(8).map(|pixels| {
+                    pixels.iter().enumerate().fold(0, |byte, (i, &pixel)| {
+                        let black = pixel != Pixel::Transparent && !pixel.is_lit();
+                        byte | u8::from(black) << (7 - i)
+                    })
+                })),
+                Format::Pgm => bytes.extend(row.iter().map(|&pixel| match pixel {
+                    Pixel::Transparent => 128,
+                    pixel => pixel.luma(),
+                })),
+                _ => bytes.extend(row.iter().flat_map(|pixel| {
+                    let [r, g, b, _] = pixel.rgba();
+                    [r, g, b]
+                })),
+            }
+        }
+    }
+    bytes
+}
+
+#[cfg(feature = "png")]
+fn png(frames: &[Image], scale: usize) -> Result<Vec<u8>, Error> {
+    use std::convert::TryFrom;
+    let failed = |err: png::EncodingError| Error::Unsupported(format!("Couldn't write PNG: {}", err));
+    let (width, height) = match frames.first() {
+        Some(frame) => (frame.pixels.width() * scale, frame.pixels.height() * scale),
+        None => return Ok(Vec::new()),
+    };
+    let too_big = || Error::Unsupported(format!("A {}x{} PNG is too big", width, height));
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(
+        &mut bytes,
+        u32::try_from(width).map_err(|_| too_big())?,
+        u32::try_from(height).map_err(|_| too_big())?,
+    );
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    if frames.len() > 1 {
+        let count = u32::try_from(frames.len()).map_err(|_| too_big())?;
+        encoder.set_animated(count, 0).map_err(failed)?;
+        // Half a second a frame.
+        encoder.set_frame_delay(1, 2).map_err(failed)?;
+    }
+    let mut writer = encoder.write_header().map_err(failed)?;
+    for frame in frames {
+        let data = scaled(frame, scale)
+            .flatten()
+            .flat_map(Pixel::rgba)
+            .collect::<Vec<_>>();
+        writer.write_image_data(&data).map_err(failed)?;
+    }
+    writer.finish().map_err(failed)?;
+    Ok(bytes)
+}
+
+#[cfg(not(feature = "png"))]
+fn png(_: &[Image], _: usize) -> Result<Vec<u8>, Error> {
+    Err(Error::Unsupported(
+        "This build can't write PNG (rebuild with --features png, or try ppm)".to_string(),
+    ))
+}
+
+/// Writes `frames` as a single image file, each pixel `scale` pixels across.
+pub(super) fn write(frames: &[Image], format: Format, scale: usize) -> Result<Vec<u8>, Error> {
+    match format {
+        Format::Png => png(frames, scale),
+        _ => Ok(netpbm(frames, format, scale)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Decoding, SpaceImage};
+    use super::*;
+    #[test]
+    fn netpbm() {
+        let image = SpaceImage::parse("0222112222120000", 2, 2).unwrap();
+        let image = image.composite(&Decoding::default()).unwrap();
+        let frame = std::slice::from_ref(&image);
+        assert_eq!(write(frame, Format::Pbm, 1).unwrap(), b"P4\n2 2\n\x80\x40");
+        let pgm = write(frame, Format::Pgm, 2).unwrap();
+        assert_eq!(pgm[..11], b"P5\n4 4\n255\n"[..]);
+        assert_eq!(pgm[11..15], [0, 0, 255, 255]);
+        assert_eq!(pgm.len(), 11 + 16);
+        let ppm = write(&[image.clone(), image], Format::Ppm, 1).unwrap();
+        assert_eq!(ppm.len(), 2 * (11 + 12));
+    }
+    #[cfg(feature = "png")]
+    #[test]
+    fn png() {
+        let image = SpaceImage::parse("0222112222120000", 2, 2).unwrap();
+        let frames = image.steps(&Decoding::default()).unwrap();
+        let png = write(&frames, Format::Png, 3).unwrap();
+        assert_eq!(png[..8], b"\x89PNG\r\n\x1a\n"[..]);
+        assert!(png.windows(4).any(|chunk| chunk == b"acTL"));
+    }
+}
@@ -0,0 +1,110 @@
+use super::Pixel;
+use std::str::FromStr;
+
+/// The colors that the digits of a Space Image Format picture stand for. Digits without a
+/// color aren't allowed in the picture.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) struct Palette {
+    colors: [Option<Pixel>; 10],
+}
+
+impl Default for Palette {
+    /// The puzzle's palette: 0 is black, 1 is white and 2 is transparent.
+    fn default() -> Self {
+        let mut colors = [None; 10];
+        colors[0] = Some(Pixel::BLACK);
+        colors[1] = Some(Pixel::WHITE);
+        colors[2] = Some(Pixel::Transparent);
+        Self { colors }
+    }
+}
+
+impl Palette {
+    /// The color for `digit`, if it has one.
+    pub(super) fn get(&self, digit: u8) -> Option<Pixel> {
+        self.colors.get(usize::from(digit)).copied().flatten()
+    }
+    /// The digits that have colors.
+    pub(super) fn digits(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..10).filter(move |&digit| self.get(digit).is_some())
+    }
+    /// The first digit with the color `pixel`, if any.
+    pub(super) fn digit(&self, pixel: Pixel) -> Option<u8> {
+        self.digits().find(|&digit| self.get(digit) == Some(pixel))
+    }
+    /// What a digit in the picture should be, such as "a color (0, 1 or 2)".
+    pub(super) fn expected(&self) -> String {
+        let digits = self.digits().map(|d| d.to_string()).collect::<Vec<_>>();
+        match digits.split_last() {
+            None => "a color (but the palette is empty)".to_string(),
+            Some((last, [])) => format!("a color ({})", last),
+            Some((last, rest)) => format!("a color ({} or {})", rest.join(", "), last),
+        }
+    }
+}
+
+/// Reads a color by name, or as `#rrggbb`.
+fn color(name: &str) -> Option<Pixel> {
+    let rgb = match name {
+        "transparent" => return Some(Pixel::Transparent),
+        "black" => [0, 0, 0],
+        "white" => [255, 255, 255],
+        "gray" | "grey" => [128, 128, 128],
+        "red" => [255, 0, 0],
+        "green" => [0, 255, 0],
+        "blue" => [0, 0, 255],
+        "yellow" => [255, 255, 0],
+        "cyan" => [0, 255, 255],
+        "magenta" => [255, 0, 255],
+        _ => {
+            let hex = name.strip_prefix('#').filter(|hex| hex.len() == 6)?;
+            let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+            [channel(0)?, channel(2)?, channel(4)?]
+        }
+    };
+    Some(Pixel::Rgb(rgb))
+}
+
+impl FromStr for Palette {
+    type Err = String;
+    /// Parses comma-separated `digit:color` pairs, such as `0:black,1:white,2:transparent`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut colors = [None; 10];
+        for entry in s.split(',') {
+            let (digit, name) = entry
+                .split_once(':')
+                .ok_or_else(|| format!("expected digit:color, found {:?}", entry))?;
+            let digit = match digit.parse::<usize>() {
+                Ok(digit) if digit < 10 => digit,
+                _ => return Err(format!("expected a digit, found {:?}", digit)),
+            };
+            if colors[digit].is_some() {
+                return Err(format!("{} has more than one color", digit));
+            }
+            colors[digit] =
+                Some(color(name).ok_or_else(|| format!("unknown color {:?}", name))?);
+        }
+        Ok(Self { colors })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parse() {
+        let palette = "0:black,1:white,2:transparent".parse::<Palette>().unwrap();
+        assert_eq!(palette, Palette::default());
+        assert_eq!(palette.expected(), "a color (0, 1 or 2)");
+        let palette = "3:#ff8000,7:transparent".parse::<Palette>().unwrap();
+        assert_eq!(palette.get(3), Some(Pixel::Rgb([255, 128, 0])));
+        assert_eq!(palette.get(0), None);
+        assert_eq!(palette.expected(), "a color (3 or 7)");
+        assert_eq!(palette.digit(Pixel::Transparent), Some(7));
+        assert_eq!(palette.digit(Pixel::WHITE), None);
+        assert!("0:black,0:white".parse::<Palette>().is_err());
+        assert!("10:black".parse::<Palette>().is_err());
+        assert!("0:#12345".parse::<Palette>().is_err());
+        assert!("0=black".parse::<Palette>().is_err());
+    }
+}
@@ -0,0 +1,89 @@
+use super::{Image, Pixel};
+use std::{fmt::Write, str::FromStr};
+
+/// How to show a picture in a terminal or a log.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum Style {
+    /// Each pixel as two spaces on a background of its color, which needs a terminal that
+    /// understands 24-bit ANSI escapes.
+    Ansi,
+    /// Lit pixels as Unicode half blocks, two rows of pixels to a line.
+    Blocks,
+    /// `#` for lit pixels and `.` for dark ones, which reads anywhere.
+    Ascii,
+}
+
+impl FromStr for Style {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ansi" => Ok(Self::Ansi),
+            "blocks" => Ok(Self::Blocks),
+            "ascii" => Ok(Self::Ascii),
+            _ => Err(format!("Unknown render style: {}", s)),
+        }
+    }
+}
+
+fn ansi(image: &Image) -> String {
+    let mut text = String::new();
+    for row in image.pixels.rows() {
+        for &pixel in row {
+            // Writing to a `String` can't fail.
+            let _ = match pixel {
+                Pixel::Rgb([r, g, b]) => write!(text, "\x1b[48;2;{};{};{}m  ", r, g, b),
+                Pixel::Transparent => write!(text, "\x1b[0m  "),
+            };
+        }
+        text.push_str("\x1b[0m\n");
+    }
+    text
+}
+
+fn blocks(image: &Image) -> String {
+    let lit = image.lit();
+    let mut text = String::new();
+    let rows = lit.rows().collect::<Vec<_>>();
+    for pair in rows.chunks(2) {
+        for col in 0..lit.width() {
+            let bottom = pair.get(1).is_some_and(|row| row[col]);
+            text.push(match (pair[0][col], bottom) {
+                (true, true) => '█',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (false, false) => ' ',
+            });
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Draws `image` in the given style, a line at a time.
+pub(super) fn render(image: &Image, style: Style) -> String {
+    match style {
+        Style::Ansi => ansi(image),
+        Style::Blocks => blocks(image),
+        Style::Ascii => image.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Decoding, SpaceImage};
+    use super::*;
+    #[test]
+    fn styles() {
+        let image = SpaceImage::parse("012211", 2, 3).unwrap();
+        let image = image.composite(&Decoding::default()).unwrap();
+        assert_eq!(render(&image, Style::Ascii), ".#\n  \n##\n");
+        assert_eq!(render(&image, Style::Blocks), " ▀\n▀▀\n");
+        assert_eq!(
+            render(&image, Style::Ansi),
+            "\x1b[48;2;0;0;0m  \x1b[48;2;255;255;255m  \x1b[0m\n\
+             \x1b[0m  \x1b[0m  \x1b[0m\n\
+             \x1b[48;2;255;255;255m  \x1b[48;2;255;255;255m  \x1b[0m\n"
+        );
+        assert!("sixel".parse::<Style>().is_err());
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+
+/// Something in the puzzle input that doesn't look like the puzzle says it should.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct ParseError {
+    /// 1-based line number.
+    pub(crate) line: usize,
+    /// 1-based column, counted in characters.
+    pub(crate) column: usize,
+    pub(crate) text: String,
+    pub(crate) expected: String,
+}
+
+impl ParseError {
+    pub(crate) fn new(line: usize, column: usize, text: &str, expected: &str) -> Self {
+        Self {
+            line,
+            column,
+            text: text.to_string(),
+            expected: expected.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, found ",
+            self.line, self.column, self.expected
+        )?;
+        if self.text.is_empty() {
+            write!(f, "nothing")
+        } else {
+            write!(f, "{:?}", self.text)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    Parse(ParseError),
+    /// A value got too big for the type it's stored in.
+    Overflow(String),
+    /// Something was asked of a day that it can't do.
+    Unsupported(String),
+    /// Input whose lines parse but don't fit together, such as an orbit map that isn't a
+    /// tree, with everything that's wrong with it.
+    Invalid {
+        what: String,
+        problems: Vec<String>,
+    },
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "Invalid input: {}", err),
+            Self::Overflow(message) => write!(f, "Overflow: {}", message),
+            Self::Unsupported(message) => write!(f, "{}", message),
+            Self::Invalid { what, problems } => {
+                write!(f, "Invalid {}: {}", what, problems.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Splits `line` on `separator`, pairing each trimmed field with its 1-based column.
+pub(crate) fn fields(line: &str, separator: char) -> impl Iterator<Item = (usize, &str)> {
+    let mut offset = 0;
+    line.split(separator).map(move |field| {
+        let leading = field.len() - field.trim_start().len();
+        let column = line[..offset + leading].chars().count() + 1;
+        offset += field.len() + separator.len_utf8();
+        (column, field.trim())
+    })
+}
+
+/// The numbered lines of `input` that have something on them.
+pub(crate) fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty())
+}
+
+/// The first line of `input`, for puzzles whose input is a single line.
+pub(crate) fn first_line<'a>(input: &'a str, expected: &str) -> Result<&'a str, ParseError> {
+    input
+        .lines()
+        .next()
+        .filter(|line| !line.trim().is_empty())
+        .ok_or_else(|| ParseError::new(1, 1, "", expected))
+}
+
+/// Parses a separated list of numbers from a single line of input.
+pub(crate) fn numbers<T: std::str::FromStr>(
+    line: usize,
+    text: &str,
+    separator: char,
+    expected: &str,
+) -> Result<Vec<T>, ParseError> {
+    fields(text, separator)
+        .map(|(column, field)| {
+            field
+                .parse()
+                .map_err(|_| ParseError::new(line, column, field, expected))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn columns() {
+        assert_eq!(
+            fields("1, 22,x", ',').collect::<Vec<_>>(),
+            vec![(1, "1"), (4, "22"), (7, "x")]
+        );
+        let err = numbers::<i32>(2, "1,2,,4", ',', "an integer").unwrap_err();
+        assert_eq!(err, ParseError::new(2, 5, "", "an integer"));
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 5: expected an integer, found nothing"
+        );
+        assert_eq!(
+            ParseError::new(1, 3, "U1x", "a step").to_string(),
+            "line 1, column 3: expected a step, found \"U1x\""
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! Checks every registered day against the worked examples in `../examples/<day>.txt`.
+//!
+//! Each file holds any number of examples. An example starts with a `===` line, optionally
+//! followed by `key=value` parameters, then the puzzle input. Each `--- part <n>` line starts the
+//! expected answer for that part, which runs until the next marker. Anything before the first
+//! example is a comment.
+
+use crate::{params::Params, solver, Part};
+
+#[derive(Debug, Default)]
+struct Example {
+    line: usize,
+    params: Params,
+    input: String,
+    answers: Vec<(Part, String)>,
+}
+
+fn parse(text: &str) -> Result<Vec<Example>, String> {
+    let mut examples = Vec::<Example>::new();
+    let mut part = None;
+    for (i, line) in text.lines().enumerate() {
+        if let Some(params) = line.strip_prefix("===") {
+            examples.push(Example {
+                line: i + 1,
+                params: params
+                    .parse()
+                    .map_err(|e| format!("line {}: {}", i + 1, e))?,
+                ..Example::default()
+            });
+            part = None;
+        } else if let Some(header) = line.strip_prefix("--- part ") {
+            let example = examples
+                .last_mut()
+                .ok_or_else(|| format!("line {}: answer before any example", i + 1))?;
+            let index = match header.trim() {
+                "1" => Part::One,
+                "2" => Part::Two,
+                other => return Err(format!("line {}: unknown part {}", i + 1, other)),
+            };
+            example.answers.push((index, String::new()));
+            part = Some(index);
+        } else if let Some(example) = examples.last_mut() {
+            let text = match part {
+                Some(_) => &mut example.answers.last_mut().unwrap().1,
+                None => &mut example.input,
+            };
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+    Ok(examples)
+}
+
+#[test]
+fn examples() {
+    let mut failures = Vec::new();
+    let mut checked = 0;
+    for day in 1..=25 {
+        let (solve, text) = match (
+            solver(day),
+            std::fs::read_to_string(format!("../examples/{}.txt", day)),
+        ) {
+            (Some(solve), Ok(text)) => (solve, text),
+            _ => continue,
+        };
+        let examples = parse(&text).unwrap_or_else(|e| panic!("examples/{}.txt {}", day, e));
+        for example in examples {
+            for (part, expected) in &example.answers {
+                // Errors are written out in full, and compared as text.
+                let (matches, answer) = match solve(&example.input, *part, &example.params) {
+                    Ok(answer) => (answer.matches(expected), format!("{:?}", answer)),
+                    Err(err) => {
+                        let err = err.to_string();
+                        (err.trim() == expected.trim(), err)
+                    }
+                };
+                checked += 1;
+                if !matches {
+                    failures.push(format!(
+                        "day {} part {} (example at line {}): expected {:?}, got {}",
+                        day, part, example.line, expected, answer
+                    ));
+                }
+            }
+        }
+    }
+    assert!(checked > 0, "No examples found");
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
@@ -1,44 +1,5 @@
-use std::{
-    collections::VecDeque,
-    convert::{TryFrom, TryInto},
-};
-
-struct Digits {
-    inner: VecDeque<u32>,
-}
-
-impl Digits {
-    fn new(val: u32) -> Self {
-        let inner = format!("{}", val)
-            .chars()
-            .map(|c| c.to_digit(10).unwrap())
-            .collect::<VecDeque<_>>();
-        Self { inner }
-    }
-}
-
-impl Iterator for Digits {
-    type Item = u32;
-    fn next(&mut self) -> Option<Self::Item> {
-        self.inner.pop_front()
-    }
-}
-
-impl DoubleEndedIterator for Digits {
-    fn next_back(&mut self) -> Option<Self::Item> {
-        self.inner.pop_back()
-    }
-}
-
-trait DigitsExt: Sized {
-    fn digits(self) -> Digits;
-}
-
-impl DigitsExt for u32 {
-    fn digits(self) -> Digits {
-        Digits::new(self)
-    }
-}
+use crate::{answer::Answer, digits::DigitsExt, error, params::Params, Part};
+use std::convert::{TryFrom, TryInto};
 
 enum Status {
     Running,
@@ -59,6 +20,17 @@ enum Error {
     UnknownOpcode(u32),
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::SignedOpcode => write!(f, "Negative opcode"),
+            Self::UnknownOpcode(opcode) => write!(f, "Unknown opcode: {}", opcode),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 impl From<TryFromError> for Error {
     fn from(err: TryFromError) -> Self {
         Self::UnknownOpcode(err.opcode)
@@ -158,21 +130,16 @@ impl Computer {
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 enum Mode {
     Immediate,
+    #[default]
     Position,
 }
 
-impl Default for Mode {
-    fn default() -> Self {
-        Self::Position
-    }
-}
-
-impl TryFrom<u32> for Mode {
+impl TryFrom<u8> for Mode {
     type Error = ();
-    fn try_from(num: u32) -> Result<Self, Self::Error> {
+    fn try_from(num: u8) -> Result<Self, Self::Error> {
         match num {
             0 => Ok(Self::Position),
             1 => Ok(Self::Immediate),
@@ -256,8 +223,8 @@ impl TryFrom<u32> for Operation {
     }
 }
 
-fn run_diagnostic(memory: &[i32], id: i32) -> String {
-    let memory = memory.iter().copied().collect();
+fn run_diagnostic(memory: &[i32], id: i32) -> Answer {
+    let memory = memory.to_vec();
     let mut comp = Computer::new(memory);
     comp.input = id;
     comp.run().unwrap();
@@ -266,26 +233,22 @@ fn run_diagnostic(memory: &[i32], id: i32) -> String {
     for o in output {
         assert_eq!(o, 0);
     }
-    format!("{}", last)
+    last.into()
 }
 
-fn part1(memory: &[i32]) -> String {
-    run_diagnostic(memory, 1)
+fn part1(memory: &[i32], id: i32) -> Answer {
+    run_diagnostic(memory, id)
 }
 
-fn part2(memory: &[i32]) -> String {
-    run_diagnostic(memory, 5)
+fn part2(memory: &[i32], id: i32) -> Answer {
+    run_diagnostic(memory, id)
 }
 
-pub(crate) fn run(input: String) -> [String; 2] {
-    let memory = input
-        .lines()
-        .next()
-        .unwrap()
-        .split(',')
-        .filter_map(|x| str::parse::<i32>(x).ok())
-        .collect::<Vec<_>>();
-    let one = part1(&memory);
-    let five = part2(&memory);
-    [one, five]
+pub(crate) fn run(input: &str, part: Part, params: &Params) -> Result<Answer, error::Error> {
+    let line = error::first_line(input, "an Intcode program")?;
+    let memory = error::numbers::<i32>(1, line, ',', "an integer")?;
+    Ok(match part {
+        Part::One => part1(&memory, params.get_or("id", 1)?),
+        Part::Two => part2(&memory, params.get_or("id", 5)?),
+    })
 }
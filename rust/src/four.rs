@@ -1,87 +1,217 @@
-struct Digits {
-    inner: Vec<u32>,
-}
+use crate::{
+    answer::Answer,
+    digits::{self, DigitsExt},
+    error::{self, Error, ParseError},
+    params::Params,
+    Part,
+};
 
-impl Digits {
-    fn new(val: u32) -> Self {
-        let inner = format!("{}", val)
-            .chars()
-            .map(|c| c.to_digit(10).unwrap())
-            .collect::<Vec<_>>();
-        Self { inner }
-    }
+use std::collections::HashMap;
+
+mod rules;
+
+use rules::{Rule, Span};
+
+/// Digit DP over the digits of an upper bound, counting passwords without listing them.
+struct Counter<'a> {
+    bound: &'a [u8],
+    runs: Span,
+    /// Counts for positions where the bound no longer constrains the remaining digits, keyed
+    /// by position, previous digit, length of the current run and whether a run has matched.
+    memo: HashMap<(usize, u8, usize, bool), u64>,
 }
 
-impl Digits {
-    fn windows(&self, size: usize) -> std::slice::Windows<u32> {
-        self.inner.windows(size)
+impl Counter<'_> {
+    fn count(&mut self, pos: usize, last: u8, run: usize, found: bool, tight: bool) -> u64 {
+        if pos == self.bound.len() {
+            return (found || self.runs.contains(run)) as u64;
+        }
+        let key = (pos, last, run, found);
+        if !tight {
+            if let Some(&count) = self.memo.get(&key) {
+                return count;
+            }
+        }
+        // Digits never decrease, and the first one can't be zero.
+        let low = last.max(1);
+        let high = if tight { self.bound[pos] } else { 9 };
+        let mut count = 0;
+        for digit in low..=high {
+            let tight = tight && digit == high;
+            count += if pos == 0 {
+                self.count(1, digit, 1, false, tight)
+            } else if digit == last {
+                self.count(pos + 1, digit, run + 1, found, tight)
+            } else {
+                let found = found || self.runs.contains(run);
+                self.count(pos + 1, digit, 1, found, tight)
+            };
+        }
+        if !tight {
+            self.memo.insert(key, count);
+        }
+        count
     }
 }
 
-impl Iterator for Digits {
-    type Item = u32;
-    fn next(&mut self) -> Option<Self::Item> {
-        self.inner.pop()
+/// Counts the passwords of exactly `length` digits that are no greater than `bound`.
+fn count_up_to(bound: u64, length: usize, runs: Span) -> u64 {
+    let digits = bound.digits().collect::<Vec<_>>();
+    let bound = match digits.len() {
+        n if n < length => return 0,
+        n if n > length => vec![9; length],
+        _ => digits,
+    };
+    Counter {
+        bound: &bound,
+        runs,
+        memo: HashMap::new(),
     }
+    .count(0, 0, 0, false, true)
 }
 
-trait DigitsExt: Sized {
-    fn digits(self) -> Digits;
-}
+/// The most numbers `count` will check one at a time.
+const SCAN_LIMIT: u64 = 10_000_000;
 
-impl DigitsExt for u32 {
-    fn digits(self) -> Digits {
-        Digits::new(self)
-    }
+/// The lengths allowed by the `length` rules in `rules`, as far as numbers up to `high` go.
+fn lengths(rules: &[Rule], high: u64) -> std::ops::RangeInclusive<usize> {
+    rules.iter().fold(1..=high.digits().len(), |lengths, rule| match rule {
+        Rule::Length(span) => {
+            let max = span.max.unwrap_or(usize::MAX);
+            *lengths.start().max(&span.min)..=*lengths.end().min(&max)
+        }
+        _ => lengths,
+    })
 }
 
-fn has_repeat(number: u32) -> bool {
-    number.digits().windows(2).any(|xs| xs[0] == xs[1])
+/// The puzzle's rules, or anything else only made of `length`, `increasing` and one `run`,
+/// can be counted without looking at the passwords one by one.
+fn countable(rules: &[Rule]) -> Option<Span> {
+    let mut runs = None;
+    for rule in rules {
+        match rule {
+            Rule::Length(_) | Rule::Increasing => {}
+            Rule::Run(span) if runs.is_none() => runs = Some(*span),
+            _ => return None,
+        }
+    }
+    match rules.contains(&Rule::Increasing) {
+        true => Some(runs.unwrap_or(Span::at_least(0))),
+        false => None,
+    }
 }
 
-fn has_unique_repeat(number: u32) -> bool {
-    number
-        .digits()
-        .map(|x| {
-            number
-                .digits()
-                .skip_while(|d| *d != x)
-                .take_while(|d| *d == x)
-                .count()
-        })
-        .any(|l| l == 2)
+/// Calls `f` with every sequence of `length` digits that never goes down (or never up, if
+/// `decreasing`), and doesn't start with zero.
+fn monotonic(length: usize, decreasing: bool, f: &mut impl FnMut(&[u8])) {
+    fn extend(digits: &mut Vec<u8>, length: usize, decreasing: bool, f: &mut impl FnMut(&[u8])) {
+        if digits.len() == length {
+            return f(digits);
+        }
+        let next = match (digits.last(), decreasing) {
+            (None, _) => 1..=9,
+            (Some(&last), false) => last..=9,
+            (Some(&last), true) => 0..=last,
+        };
+        for digit in next {
+            digits.push(digit);
+            extend(digits, length, decreasing, f);
+            digits.pop();
+        }
+    }
+    extend(&mut Vec::with_capacity(length), length, decreasing, f)
 }
 
-fn has_six_digits(number: u32) -> bool {
-    (100_000..1_000_000).contains(&number)
+/// Counts the numbers in `low..=high` that satisfy `rule`.
+fn count(low: u64, high: u64, rule: &Rule) -> Result<u64, Error> {
+    if low > high {
+        return Ok(0);
+    }
+    // The fast paths only build numbers that don't start with zero, which leaves out 0 itself.
+    if low == 0 {
+        let rest = match high {
+            0 => 0,
+            _ => count(1, high, rule)?,
+        };
+        return Ok(rule.matches(0) as u64 + rest);
+    }
+    let rules = rule.conjuncts();
+    let lengths = lengths(rules, high);
+    if let Some(runs) = countable(rules) {
+        let below = |length| match low.checked_sub(1) {
+            Some(bound) => count_up_to(bound, length, runs),
+            None => 0,
+        };
+        return Ok(lengths
+            .map(|length| count_up_to(high, length, runs) - below(length))
+            .sum());
+    }
+    let increasing = rules.contains(&Rule::Increasing);
+    if increasing || rules.contains(&Rule::Decreasing) {
+        let mut count = 0;
+        for length in lengths {
+            monotonic(length, !increasing, &mut |digits| {
+                // Twenty digits can be more than a `u64` holds, and more than `high` anyway.
+                let number = digits::from_digits::<u64>(digits.iter().copied(), 10);
+                if number.is_some_and(|n| (low..=high).contains(&n) && rule.matches(n)) {
+                    count += 1;
+                }
+            });
+        }
+        return Ok(count);
+    }
+    if high - low >= SCAN_LIMIT {
+        return Err(Error::Unsupported(
+            "There are too many numbers to check one at a time \
+             (add increasing or decreasing to the rule)"
+                .to_string(),
+        ));
+    }
+    Ok((low..=high).filter(|&number| rule.matches(number)).count() as u64)
 }
 
-fn has_increasing_digits(number: u32) -> bool {
-    number.digits().windows(2).all(|xs| xs[0] <= xs[1])
+/// The puzzle's rules for passwords of `length` digits.
+fn puzzle(part: Part, length: usize) -> Rule {
+    let runs = match part {
+        Part::One => Span::at_least(2),
+        Part::Two => Span::exactly(2),
+    };
+    Rule::All(vec![
+        Rule::Length(Span::exactly(length)),
+        Rule::Increasing,
+        Rule::Run(runs),
+    ])
 }
 
-fn is_valid_password(number: u32) -> bool {
-    has_repeat(number) && has_six_digits(number) & has_increasing_digits(number)
-}
+const RANGE: &str = "a range of passwords like 123456-654321";
 
-fn is_valid_ignoring_chunks(number: u32) -> bool {
-    has_unique_repeat(number) && has_six_digits(number) & has_increasing_digits(number)
-}
+/// The most digits a password can have and still fit in a `u64`.
+const MAX_LENGTH: usize = 18;
 
-pub(crate) fn run(input: String) -> [String; 2] {
-    let mut range = input
-        .lines()
-        .next()
-        .unwrap()
-        .split('-')
-        .map(|x| x.parse::<u32>().unwrap());
-    let (l, u) = (range.next().unwrap(), range.next().unwrap());
-    let range = l..=u;
-    let part1 = range.clone().filter(|x| is_valid_password(*x)).count();
-    let part1 = format!("{}", part1);
-    let part2 = range.filter(|x| is_valid_ignoring_chunks(*x)).count();
-    let part2 = format!("{}", part2);
-    [part1, part2]
+/// Counts the passwords in the range that follow the puzzle's rules, or the rule given as
+/// `rule=...` (written without spaces, such as `length(6)&increasing&run(2..)`).
+pub(crate) fn run(input: &str, part: Part, params: &Params) -> Result<Answer, Error> {
+    let line = error::first_line(input, RANGE)?;
+    let (l, u) = match error::numbers::<u64>(1, line, '-', RANGE)?[..] {
+        [l, u] => (l, u),
+        _ => return Err(ParseError::new(1, 1, line, RANGE).into()),
+    };
+    let rule = match params.get::<String>("rule")? {
+        Some(rule) => rule
+            .parse()
+            .map_err(|err| Error::Unsupported(format!("Invalid rule: {}", err)))?,
+        None => {
+            let length = params.get_or("length", 6)?;
+            if !(1..=MAX_LENGTH).contains(&length) {
+                return Err(Error::Unsupported(format!(
+                    "Passwords must have between 1 and {} digits",
+                    MAX_LENGTH
+                )));
+            }
+            puzzle(part, length)
+        }
+    };
+    Ok(count(l, u, &rule)?.into())
 }
 
 #[cfg(test)]
@@ -89,14 +219,43 @@ mod tests {
     use super::*;
     #[test]
     fn passwords() {
-        assert!(is_valid_password(111111));
-        assert!(!is_valid_password(223450));
-        assert!(!is_valid_password(123789));
+        let rule = puzzle(Part::One, 6);
+        assert!(rule.matches(111111));
+        assert!(!rule.matches(223450));
+        assert!(!rule.matches(123789));
     }
     #[test]
     fn passwords_chunks() {
-        assert!(is_valid_ignoring_chunks(112233));
-        assert!(is_valid_ignoring_chunks(111122));
-        assert!(!is_valid_ignoring_chunks(123444));
+        let rule = puzzle(Part::Two, 6);
+        assert!(rule.matches(112233));
+        assert!(rule.matches(111122));
+        assert!(!rule.matches(123444));
+    }
+    #[test]
+    fn counting() {
+        let scan = |low, high, rule: &Rule| {
+            (low..=high)
+                .filter(|&number| rule.matches(number))
+                .count() as u64
+        };
+        let rules = [
+            puzzle(Part::One, 6),
+            puzzle(Part::Two, 6),
+            "length(3..=5)&increasing".parse().unwrap(),
+            "decreasing&run(3..)".parse().unwrap(),
+            "increasing&!run(2..)|digits(12)".parse().unwrap(),
+            Rule::Increasing,
+            "length(1)&increasing".parse().unwrap(),
+        ];
+        for (low, high) in [(100_000, 180_000), (356_261, 400_000), (111_111, 111_111), (0, 9), (0, 0)] {
+            for rule in &rules {
+                assert_eq!(count(low, high, rule).unwrap(), scan(low, high, rule), "{:?}", rule);
+            }
+        }
+        // Eighteen digits from 1 to 9 can't all differ, so every non-decreasing choice of
+        // them counts: 26 choose 8.
+        let rule = "length(18)&increasing&run(2..)".parse().unwrap();
+        assert_eq!(count(0, u64::MAX, &rule).unwrap(), 1_562_275);
+        assert!(count(0, u64::MAX, &Rule::Run(Span::at_least(2))).is_err());
     }
 }
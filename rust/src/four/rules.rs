@@ -0,0 +1,275 @@
+use crate::digits::DigitsExt;
+use std::{slice, str::FromStr};
+
+/// An inclusive range of lengths, written like a Rust range: `2`, `2..`, `..=4`, `2..4` or
+/// `2..=4`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) struct Span {
+    pub(super) min: usize,
+    pub(super) max: Option<usize>,
+}
+
+impl Span {
+    pub(super) fn exactly(n: usize) -> Self {
+        Self {
+            min: n,
+            max: Some(n),
+        }
+    }
+    pub(super) fn at_least(n: usize) -> Self {
+        Self { min: n, max: None }
+    }
+    pub(super) fn contains(&self, n: usize) -> bool {
+        n >= self.min && self.max.is_none_or(|max| n <= max)
+    }
+}
+
+impl FromStr for Span {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |s: &str| {
+            s.trim()
+                .parse::<usize>()
+                .map_err(|_| format!("expected a length, found {:?}", s.trim()))
+        };
+        let bound = |s: &str| match s.trim() {
+            "" => Ok(None),
+            s => number(s).map(Some),
+        };
+        let (min, max) = match s.split_once("..") {
+            None => {
+                let n = number(s)?;
+                (n, Some(n))
+            }
+            Some((min, max)) => {
+                let min = bound(min)?.unwrap_or(0);
+                let max = match max.strip_prefix('=') {
+                    Some(max) => Some(number(max)?),
+                    None => match bound(max)? {
+                        Some(0) => return Err(format!("{:?} is empty", s)),
+                        max => max.map(|max| max - 1),
+                    },
+                };
+                (min, max)
+            }
+        };
+        Ok(Self { min, max })
+    }
+}
+
+/// A condition on the digits of a password.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) enum Rule {
+    /// The password has this many digits.
+    Length(Span),
+    /// No digit is smaller than the one before it.
+    Increasing,
+    /// No digit is larger than the one before it.
+    Decreasing,
+    /// Some run of equal digits, taken as long as it goes, has a length in the span.
+    Run(Span),
+    /// Every digit is in the set, one bit per digit.
+    Digits(u16),
+    Not(Box<Rule>),
+    All(Vec<Rule>),
+    Any(Vec<Rule>),
+}
+
+impl Rule {
+    pub(super) fn matches(&self, number: u64) -> bool {
+        match self {
+            Self::Length(span) => span.contains(number.digits().len()),
+            Self::Increasing => number.digits().windows().all(|[a, b]| a <= b),
+            Self::Decreasing => number.digits().windows().all(|[a, b]| a >= b),
+            Self::Run(span) => number.digits().runs().any(|(_, len)| span.contains(len)),
+            Self::Digits(set) => number.digits().all(|digit| set & (1 << digit) != 0),
+            Self::Not(rule) => !rule.matches(number),
+            Self::All(rules) => rules.iter().all(|rule| rule.matches(number)),
+            Self::Any(rules) => rules.iter().any(|rule| rule.matches(number)),
+        }
+    }
+    /// The rules that all have to hold for this one to.
+    pub(super) fn conjuncts(&self) -> &[Rule] {
+        match self {
+            Self::All(rules) => rules,
+            rule => slice::from_ref(rule),
+        }
+    }
+}
+
+/// Reads rules such as `length(6)&increasing&run(2)`. `&` binds tighter than `|`, `!` negates,
+/// and parentheses group. There are no spaces, since parameters are separated by them.
+impl FromStr for Rule {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(pos) = s.find(char::is_whitespace) {
+            let column = s[..pos].chars().count() + 1;
+            return Err(format!("column {}: rules are written without spaces", column));
+        }
+        let mut parser = Parser { text: s, pos: 0 };
+        let rule = parser.any()?;
+        match parser.peek() {
+            None => Ok(rule),
+            Some(_) => Err(parser.error("& or |")),
+        }
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn rest(&self) -> &str {
+        &self.text[self.pos..]
+    }
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += c.len_utf8();
+        }
+        found
+    }
+    fn error(&self, expected: &str) -> String {
+        let column = self.text[..self.pos].chars().count() + 1;
+        match self.rest() {
+            "" => format!("column {}: expected {}, found nothing", column, expected),
+            rest => format!("column {}: expected {}, found {:?}", column, expected, rest),
+        }
+    }
+    fn any(&mut self) -> Result<Rule, String> {
+        let mut rules = vec![self.all()?];
+        while self.eat('|') {
+            rules.push(self.all()?);
+        }
+        Ok(match rules.len() {
+            1 => rules.remove(0),
+            _ => Rule::Any(rules),
+        })
+    }
+    fn all(&mut self) -> Result<Rule, String> {
+        let mut rules = vec![self.not()?];
+        while self.eat('&') {
+            rules.push(self.not()?);
+        }
+        Ok(match rules.len() {
+            1 => rules.remove(0),
+            _ => Rule::All(rules),
+        })
+    }
+    fn not(&mut self) -> Result<Rule, String> {
+        if self.eat('!') {
+            Ok(Rule::Not(Box::new(self.not()?)))
+        } else if self.eat('(') {
+            let rule = self.any()?;
+            if !self.eat(')') {
+                return Err(self.error(")"));
+            }
+            Ok(rule)
+        } else {
+            self.rule()
+        }
+    }
+    fn rule(&mut self) -> Result<Rule, String> {
+        const RULE: &str = "a rule (length, increasing, decreasing, run or digits)";
+        let start = self.pos;
+        let len = self
+            .rest()
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(self.rest().len());
+        let name = &self.text[start..start + len];
+        self.pos += len;
+        let argument = if self.eat('(') {
+            let len = self
+                .rest()
+                .find(')')
+                .ok_or_else(|| self.error("an argument followed by )"))?;
+            let argument = (self.pos, &self.text[self.pos..self.pos + len]);
+            self.pos += len + 1;
+            Some(argument)
+        } else {
+            None
+        };
+        // Reports a bad argument at the column where it starts.
+        let invalid = |pos: usize, err: String| {
+            let column = self.text[..pos].chars().count() + 1;
+            format!("column {}: {}", column, err)
+        };
+        let span = |argument: Option<(usize, &str)>| match argument {
+            Some((pos, text)) => text.parse::<Span>().map_err(|err| invalid(pos, err)),
+            None => Err(invalid(self.pos, format!("{} needs a length", name))),
+        };
+        match (name, argument) {
+            ("length", argument) => Ok(Rule::Length(span(argument)?)),
+            ("run", argument) => Ok(Rule::Run(span(argument)?)),
+            ("increasing", None) => Ok(Rule::Increasing),
+            ("decreasing", None) => Ok(Rule::Decreasing),
+            ("digits", Some((pos, text))) => text
+                .chars()
+                .try_fold(0, |set, c| match c.to_digit(10) {
+                    Some(digit) => Ok(set | 1 << digit),
+                    None => Err(invalid(pos, format!("expected digits, found {:?}", text))),
+                })
+                .map(Rule::Digits),
+            ("increasing", Some((pos, _))) | ("decreasing", Some((pos, _))) => {
+                Err(invalid(pos, format!("{} takes no argument", name)))
+            }
+            ("digits", None) => Err(invalid(self.pos, "digits needs a set".to_string())),
+            _ => {
+                self.pos = start;
+                Err(self.error(RULE))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parsing() {
+        assert_eq!(
+            "length(6)&increasing&(run(2..)|!digits(13579))".parse(),
+            Ok(Rule::All(vec![
+                Rule::Length(Span::exactly(6)),
+                Rule::Increasing,
+                Rule::Any(vec![
+                    Rule::Run(Span::at_least(2)),
+                    Rule::Not(Box::new(Rule::Digits(0b10_1010_1010))),
+                ]),
+            ]))
+        );
+        assert_eq!("..=4".parse(), Ok(Span { min: 0, max: Some(4) }));
+        assert_eq!("2..4".parse(), Ok(Span { min: 2, max: Some(3) }));
+        assert_eq!(
+            "length(6)&increasing)".parse::<Rule>(),
+            Err("column 21: expected & or |, found \")\"".to_string())
+        );
+        assert_eq!(
+            "run(2)|odd".parse::<Rule>(),
+            Err("column 8: expected a rule (length, increasing, decreasing, run or digits), \
+                 found \"odd\""
+                .to_string())
+        );
+        assert_eq!(
+            "length(6) & increasing".parse::<Rule>(),
+            Err("column 10: rules are written without spaces".to_string())
+        );
+        assert_eq!(
+            "run(x)".parse::<Rule>(),
+            Err("column 5: expected a length, found \"x\"".to_string())
+        );
+    }
+    #[test]
+    fn matching() {
+        let rule = "increasing&run(2)".parse::<Rule>().unwrap();
+        assert!(rule.matches(112233));
+        assert!(rule.matches(111122));
+        assert!(!rule.matches(123444));
+        assert!(!rule.matches(223450));
+    }
+}
@@ -0,0 +1,391 @@
+//! Points, directions and grids shared between days.
+//!
+//! Points are `(x, y)` pairs. Directions treat y as growing upwards, like the wires of day 3;
+//! dense grids are indexed with x as the column and y as the row, counting down from the top.
+
+use std::{
+    collections::HashMap,
+    convert::{TryFrom, TryInto},
+    fmt,
+    hash::Hash,
+    ops::{Add, AddAssign, Div, Index, IndexMut, Mul, Neg, Sub},
+};
+
+/// A position on a grid.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub(crate) struct Point<T = i32> {
+    pub(crate) x: T,
+    pub(crate) y: T,
+}
+
+/// The displacement from one point to another.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub(crate) struct Vector<T = i32> {
+    pub(crate) x: T,
+    pub(crate) y: T,
+}
+
+impl<T> Point<T> {
+    pub(crate) const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> Vector<T> {
+    pub(crate) const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+/// Coordinates that can be offset by small constants, which is all the neighbour and rotation
+/// helpers need.
+pub(crate) trait Coordinate:
+    Copy + Add<Output = Self> + Sub<Output = Self> + Neg<Output = Self> + Ord + From<i8>
+{
+}
+
+impl<T> Coordinate for T where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Neg<Output = T> + Ord + From<i8>
+{
+}
+
+impl<T: Coordinate> Point<T> {
+    pub(crate) fn origin() -> Self {
+        Self::new(T::from(0), T::from(0))
+    }
+    /// The points directly above, below, left and right of this one.
+    #[allow(dead_code)]
+    pub(crate) fn neighbors4(self) -> [Self; 4] {
+        Direction::ALL.map(|direction| self + direction.unit())
+    }
+    /// The points surrounding this one, diagonals included.
+    #[allow(dead_code)]
+    pub(crate) fn neighbors8(self) -> [Self; 8] {
+        let (one, zero) = (T::from(1), T::from(0));
+        [
+            Vector::new(-one, one),
+            Vector::new(zero, one),
+            Vector::new(one, one),
+            Vector::new(-one, zero),
+            Vector::new(one, zero),
+            Vector::new(-one, -one),
+            Vector::new(zero, -one),
+            Vector::new(one, -one),
+        ]
+        .map(|offset| self + offset)
+    }
+    /// Manhattan distance between two points.
+    #[allow(dead_code)]
+    pub(crate) fn manhattan(self, other: Self) -> T {
+        (other - self).manhattan()
+    }
+}
+
+impl<T: Coordinate> Vector<T> {
+    /// The sum of the absolute values of the components.
+    #[allow(dead_code)]
+    pub(crate) fn manhattan(self) -> T {
+        let abs = |v: T| if v < T::from(0) { -v } else { v };
+        abs(self.x) + abs(self.y)
+    }
+    /// Rotates a quarter turn anticlockwise.
+    #[allow(dead_code)]
+    pub(crate) fn rotate_left(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+    /// Rotates a quarter turn clockwise.
+    #[allow(dead_code)]
+    pub(crate) fn rotate_right(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl<T: Add<Output = T>> Add<Vector<T>> for Point<T> {
+    type Output = Self;
+    fn add(self, other: Vector<T>) -> Self::Output {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Add<Output = T> + Copy> AddAssign<Vector<T>> for Point<T> {
+    fn add_assign(&mut self, other: Vector<T>) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Sub<Output = T>> Sub<Self> for Point<T> {
+    type Output = Vector<T>;
+    fn sub(self, other: Self) -> Self::Output {
+        Vector::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Add<Output = T>> Add<Self> for Vector<T> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self::Output {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Vector<T> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Vector<T> {
+    type Output = Self;
+    fn mul(self, c: T) -> Self::Output {
+        Self::new(c * self.x, c * self.y)
+    }
+}
+
+impl<T: Div<Output = T> + Copy> Div<T> for Vector<T> {
+    type Output = Self;
+    fn div(self, c: T) -> Self::Output {
+        Self::new(self.x / c, self.y / c)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(crate) enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Every direction, clockwise from up.
+    #[allow(dead_code)]
+    pub(crate) const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+    pub(crate) fn unit<T: Coordinate>(self) -> Vector<T> {
+        let (one, zero) = (T::from(1), T::from(0));
+        match self {
+            Self::Up => Vector::new(zero, one),
+            Self::Down => Vector::new(zero, -one),
+            Self::Left => Vector::new(-one, zero),
+            Self::Right => Vector::new(one, zero),
+        }
+    }
+    #[allow(dead_code)]
+    pub(crate) fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+    #[allow(dead_code)]
+    pub(crate) fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+    #[allow(dead_code)]
+    pub(crate) fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = ();
+    /// Parses the `U`, `D`, `L` and `R` the puzzles tend to use.
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'U' => Ok(Self::Up),
+            'D' => Ok(Self::Down),
+            'R' => Ok(Self::Right),
+            'L' => Ok(Self::Left),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A rectangular grid with a value in every cell, stored row by row.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+}
+
+impl<T: Clone> Grid<T> {
+    #[allow(dead_code)]
+    pub(crate) fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            cells: vec![fill; width * height],
+            width,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Lays `cells` out in rows of `width`, provided they fill the last row exactly.
+    pub(crate) fn from_cells(width: usize, cells: Vec<T>) -> Option<Self> {
+        if width > 0 && cells.len().is_multiple_of(width) {
+            Some(Self { cells, width })
+        } else {
+            None
+        }
+    }
+    pub(crate) fn width(&self) -> usize {
+        self.width
+    }
+    pub(crate) fn height(&self) -> usize {
+        self.cells.len().checked_div(self.width).unwrap_or(0)
+    }
+    fn offset<C: TryInto<usize>>(&self, point: Point<C>) -> Option<usize> {
+        let (x, y) = (point.x.try_into().ok()?, point.y.try_into().ok()?);
+        if x < self.width && y < self.height() {
+            Some(y * self.width + x)
+        } else {
+            None
+        }
+    }
+    /// The cell at `point`, if that's inside the grid.
+    pub(crate) fn get<C: TryInto<usize>>(&self, point: Point<C>) -> Option<&T> {
+        self.offset(point).map(|i| &self.cells[i])
+    }
+    pub(crate) fn get_mut<C: TryInto<usize>>(&mut self, point: Point<C>) -> Option<&mut T> {
+        self.offset(point).map(move |i| &mut self.cells[i])
+    }
+    pub(crate) fn rows(&self) -> std::slice::Chunks<'_, T> {
+        self.cells.chunks(self.width.max(1))
+    }
+    pub(crate) fn cells(&self) -> &[T] {
+        &self.cells
+    }
+    #[allow(dead_code)]
+    pub(crate) fn cells_mut(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+    /// Every cell along with its position.
+    #[allow(dead_code)]
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (Point<usize>, &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| (Point::new(i % width, i / width), cell))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+    /// Indexes by `(row, col)`, panicking outside the grid.
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        self.get(Point::new(col, row))
+            .unwrap_or_else(|| panic!("({}, {}) is outside the grid", row, col))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        self.get_mut(Point::new(col, row))
+            .unwrap_or_else(|| panic!("({}, {}) is outside the grid", row, col))
+    }
+}
+
+/// A grid that only stores the cells that have something in them, and can grow in any
+/// direction.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SparseGrid<T, C = i32>
+where
+    C: Eq + Hash,
+{
+    cells: HashMap<Point<C>, T>,
+}
+
+impl<T, C: Copy + Eq + Hash + Ord> SparseGrid<T, C> {
+    pub(crate) fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+        }
+    }
+    pub(crate) fn get(&self, point: Point<C>) -> Option<&T> {
+        self.cells.get(&point)
+    }
+    #[allow(dead_code)]
+    pub(crate) fn insert(&mut self, point: Point<C>, value: T) -> Option<T> {
+        self.cells.insert(point, value)
+    }
+    pub(crate) fn entry(
+        &mut self,
+        point: Point<C>,
+    ) -> std::collections::hash_map::Entry<'_, Point<C>, T> {
+        self.cells.entry(point)
+    }
+    #[allow(dead_code)]
+    pub(crate) fn len(&self) -> usize {
+        self.cells.len()
+    }
+    #[allow(dead_code)]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+    #[allow(dead_code)]
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (Point<C>, &T)> {
+        self.cells.iter().map(|(point, value)| (*point, value))
+    }
+    /// The corners of the smallest rectangle holding every filled cell, smallest first.
+    #[allow(dead_code)]
+    pub(crate) fn bounds(&self) -> Option<(Point<C>, Point<C>)> {
+        let mut points = self.cells.keys();
+        let first = *points.next()?;
+        Some(points.fold((first, first), |(lo, hi), p| {
+            (
+                Point::new(lo.x.min(p.x), lo.y.min(p.y)),
+                Point::new(hi.x.max(p.x), hi.y.max(p.y)),
+            )
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn points() {
+        let p = Point::new(2, -3);
+        assert_eq!(p + Vector::new(1, 1), Point::new(3, -2));
+        assert_eq!(p - Point::origin(), Vector::new(2, -3));
+        assert_eq!(p.manhattan(Point::origin()), 5);
+        assert!(p.neighbors4().contains(&Point::new(2, -2)));
+        assert_eq!(p.neighbors8().len(), 8);
+        assert!(!p.neighbors8().contains(&p));
+        assert_eq!(Vector::new(1, 2).rotate_left(), Vector::new(-2, 1));
+        assert_eq!(Vector::new(1, 2).rotate_right(), Vector::new(2, -1));
+    }
+    #[test]
+    fn directions() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.reverse(), Direction::Right);
+        for direction in Direction::ALL.iter() {
+            let unit = direction.unit::<i32>();
+            assert_eq!(direction.turn_left().unit(), unit.rotate_left());
+            assert_eq!(direction.turn_right().unit(), unit.rotate_right());
+        }
+    }
+    #[test]
+    fn grids() {
+        let mut grid = Grid::from_cells(3, (0..6).collect()).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 0)], 3);
+        assert_eq!(grid.get(Point::new(2, 1)), Some(&5));
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.get(Point::new(-1, 0)), None);
+        grid[(0, 2)] = 9;
+        assert_eq!(grid.rows().next(), Some(&[0, 1, 9][..]));
+        assert!(Grid::from_cells(4, vec![0; 6]).is_none());
+        let mut sparse = SparseGrid::new();
+        assert_eq!(sparse.bounds(), None);
+        sparse.insert(Point::new(-2, 5), 'a');
+        sparse.insert(Point::new(3, -1), 'b');
+        assert_eq!(
+            sparse.bounds(),
+            Some((Point::new(-2, -1), Point::new(3, 5)))
+        );
+    }
+}
@@ -1,34 +1,294 @@
-use clap::{App, Arg};
-use std::str::FromStr;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use output::{Format, Outcome, Record};
+use params::Params;
+use std::{
+    any::Any,
+    io::{IsTerminal, Write},
+    panic::{self, AssertUnwindSafe},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::Instant,
+};
 
-const ALL: &'static str = "1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20,21,22,23,24,25";
+const ALL: &str = "1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20,21,22,23,24,25";
 
-mod eight;
-mod five;
-mod four;
-mod one;
-mod seven;
-mod six;
-mod three;
-mod two;
+mod answer;
+mod digits;
+mod error;
+#[cfg(test)]
+mod examples;
+mod grid;
+mod ocr;
+mod output;
+mod params;
+mod scaffold;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl std::fmt::Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::One => write!(f, "1"),
+            Self::Two => write!(f, "2"),
+        }
+    }
+}
+
+type Solver = fn(&str, Part, &Params) -> Result<answer::Answer, error::Error>;
+
+/// Draws a day's input in the given format.
+type Drawer = fn(&str, &str, &Params) -> Result<Vec<u8>, error::Error>;
+
+/// Declares each day's module and wires it into the dispatch table.
+macro_rules! days {
+    ($($day:literal => $name:ident,)*) => {
+        $(mod $name;)*
+
+        fn solver(day: u8) -> Option<Solver> {
+            match day {
+                $($day => Some($name::run),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+// `aoc-2019 new <day>` keeps this list up to date.
+days! {
+    1 => one,
+    2 => two,
+    3 => three,
+    4 => four,
+    5 => five,
+    6 => six,
+    7 => seven,
+    8 => eight,
+}
 
 fn input(day: u8) -> Option<String> {
     let path = format!("../inputs/{}.txt", day);
     std::fs::read_to_string(path).ok()
 }
 
-fn run(day: u8) -> Option<[String; 2]> {
-    input(day).and_then(|input| match day {
-        1 => one::run(input).into(),
-        2 => two::run(input).into(),
-        3 => three::run(input).into(),
-        4 => four::run(input).into(),
-        5 => five::run(input).into(),
-        6 => six::run(input).into(),
-        7 => seven::run(input).into(),
-        8 => eight::run(input).into(),
+fn drawer(day: u8) -> Option<Drawer> {
+    match day {
+        3 => Some(three::draw),
+        6 => Some(six::draw),
+        8 => Some(eight::draw),
         _ => None,
-    })
+    }
+}
+
+fn run(day: u8, params: &Params) -> Vec<Record> {
+    let solve = solver(day);
+    let input = input(day);
+    Part::ALL
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let outcome = match (solve, &input) {
+                (None, _) => Outcome::Unimplemented,
+                (Some(_), None) => Outcome::Failed(format!("No input for day {}", day)),
+                (Some(solve), Some(input)) => {
+                    match panic::catch_unwind(AssertUnwindSafe(|| solve(input, part, params))) {
+                        Ok(Ok(answer)) => Outcome::Solved(answer),
+                        Ok(Err(err)) => Outcome::Failed(err.to_string()),
+                        Err(payload) => panicked(payload),
+                    }
+                }
+            };
+            Record {
+                day,
+                part,
+                outcome,
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect()
+}
+
+fn panicked(payload: Box<dyn Any + Send>) -> Outcome {
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Unknown panic".to_string());
+    // `unimplemented!()` and `todo!()` stubs aren't failures, just days still to be done.
+    if message.starts_with("not implemented") || message.starts_with("not yet implemented") {
+        Outcome::Unimplemented
+    } else {
+        Outcome::Failed(format!("panicked: {}", message))
+    }
+}
+
+/// Runs each day on one of `jobs` worker threads, returning the records in the order the days
+/// were given.
+fn run_all(days: &[u8], params: &Params, jobs: usize) -> Vec<Record> {
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(vec![Vec::new(); days.len()]);
+    // Panics are reported as records, so the default hook's stderr noise isn't wanted.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, days.len().max(1)) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                if let Some(&day) = days.get(i) {
+                    let records = run(day, params);
+                    results.lock().unwrap()[i] = records;
+                } else {
+                    break;
+                }
+            });
+        }
+    });
+    panic::set_hook(hook);
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// Fails any part whose answer isn't the one given with `--expect day.part=answer`, and
+/// returns a message for each expectation that wasn't met, including those for days that
+/// failed, aren't implemented or weren't run.
+fn check(records: &mut [Record], expected: &[(u8, Part, String)]) -> Vec<String> {
+    let mut unmet = Vec::new();
+    for (day, part, expected) in expected {
+        let record = records
+            .iter_mut()
+            .find(|record| (record.day, record.part) == (*day, *part));
+        let problem = match record {
+            None => format!("day {} wasn't run", day),
+            Some(record) => match &record.outcome {
+                Outcome::Solved(answer) if answer.matches(expected) => continue,
+                Outcome::Solved(answer) => {
+                    let message = format!("expected {}, got {}", expected, answer);
+                    record.outcome = Outcome::Failed(message.clone());
+                    message
+                }
+                Outcome::Failed(err) => err.clone(),
+                Outcome::Unimplemented => {
+                    let message = format!("expected {}, but it isn't implemented", expected);
+                    record.outcome = Outcome::Failed(message.clone());
+                    message
+                }
+            },
+        };
+        unmet.push(format!("Day {} part {}: {}", day, part, problem));
+    }
+    unmet
+}
+
+fn expectation(text: &str) -> Option<(u8, Part, String)> {
+    let (which, answer) = text.split_once('=')?;
+    let (day, part) = which.split_once('.')?;
+    let part = match part {
+        "1" => Part::One,
+        "2" => Part::Two,
+        _ => return None,
+    };
+    Some((day.parse().ok()?, part, answer.to_string()))
+}
+
+fn param() -> Arg<'static, 'static> {
+    Arg::with_name("param")
+        .short("p")
+        .long("param")
+        .takes_value(true)
+        .multiple(true)
+        .number_of_values(1)
+        .help("Overrides a puzzle parameter, e.g. width=25")
+}
+
+/// Flags that set a parameter of the same name, with their help.
+const SHORTHANDS: &[(&str, &str)] = &[
+    ("mass", "Day 1: the fuel for these masses, not the input's"),
+    ("from", "Day 6: transfer from this body, not YOU"),
+    ("to", "Day 6: transfer to this body, not SAN"),
+    ("width", "Day 8: the image width in pixels"),
+    ("height", "Day 8: the image height in pixels"),
+];
+
+fn shorthands() -> Vec<Arg<'static, 'static>> {
+    SHORTHANDS
+        .iter()
+        .map(|(name, help)| Arg::with_name(name).long(name).takes_value(true).help(help))
+        .collect()
+}
+
+fn params(matches: &ArgMatches) -> Params {
+    let parsed = matches
+        .values_of("param")
+        .map(|values| values.collect::<Vec<_>>().join(" "))
+        .unwrap_or_default()
+        .parse::<Params>();
+    let mut params = match parsed {
+        Ok(params) => params,
+        Err(err) => {
+            eprintln!("Invalid parameter: {}", err);
+            std::process::exit(1);
+        }
+    };
+    for (name, _) in SHORTHANDS {
+        if let Some(value) = matches.value_of(name) {
+            params.insert(name, value);
+        }
+    }
+    params
+}
+
+/// Sets how day 8 shows pictures. `auto` picks half blocks when the answers go to a terminal
+/// as text, and plain ASCII for pipes, files, JSON and CSV, unless `-p render=...` says
+/// otherwise.
+fn render(matches: &ArgMatches, format: Format, params: &mut Params) {
+    let style = match matches.value_of("render") {
+        Some("auto") | None if params.contains("render") => return,
+        Some("auto") | None => match format == Format::Text && std::io::stdout().is_terminal() {
+            true => "blocks",
+            false => "ascii",
+        },
+        Some(style) => style,
+    };
+    params.insert("render", style);
+}
+
+fn draw(matches: &ArgMatches) -> Result<(), String> {
+    let day = matches
+        .value_of("day")
+        .map(u8::from_str)
+        .unwrap()
+        .map_err(|_| "Invalid day".to_string())?;
+    let draw = drawer(day).ok_or_else(|| format!("Day {} can't be drawn", day))?;
+    let input = match matches.value_of("input") {
+        Some(path) => std::fs::read_to_string(path).map_err(|err| err.to_string())?,
+        None => input(day).ok_or_else(|| format!("No input for day {}", day))?,
+    };
+    let drawing = draw(
+        &input,
+        matches.value_of("format").unwrap(),
+        &params(matches),
+    )
+    .map_err(|err| err.to_string())?;
+    match matches.value_of("output") {
+        Some(path) => std::fs::write(path, drawing),
+        None => std::io::stdout().write_all(&drawing),
+    }
+    .map_err(|err| err.to_string())
 }
 
 fn main() {
@@ -37,21 +297,201 @@ fn main() {
             Arg::with_name("days")
                 .short("d")
                 .long("day")
-                .takes_value(true),
+                .takes_value(true)
+                .validator(
+                    |days| match days.split(',').all(|day| day.parse::<u8>().is_ok()) {
+                        true => Ok(()),
+                        false => Err("expected days like 1,3,8".to_string()),
+                    },
+                ),
+        )
+        .arg(
+            Arg::with_name("format")
+                .short("f")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["text", "json", "csv"])
+                .default_value("text"),
+        )
+        .arg(
+            Arg::with_name("jobs")
+                .short("j")
+                .long("jobs")
+                .takes_value(true)
+                .validator(|jobs| match jobs.parse::<usize>() {
+                    Ok(_) => Ok(()),
+                    Err(_) => Err("expected a number of threads".to_string()),
+                })
+                .default_value("1"),
+        )
+        .arg(
+            Arg::with_name("render")
+                .long("render")
+                .takes_value(true)
+                .possible_values(&["auto", "ansi", "blocks", "ascii"])
+                .default_value("auto")
+                .help("How to show pictures, such as day 8's with -p picture=true"),
+        )
+        .arg(
+            Arg::with_name("expect")
+                .long("expect")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Fails a part unless it gets this answer, e.g. 8.2=ZFLBY"),
+        )
+        .arg(param())
+        .args(&shorthands())
+        .subcommand(
+            SubCommand::with_name("new")
+                .about("Scaffolds the module and input file for a new day")
+                .arg(Arg::with_name("day").required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("draw")
+                .about("Draws a day's input, such as the orbits for day 6 or the image for day 8")
+                .arg(Arg::with_name("day").required(true))
+                .arg(Arg::with_name("format").required(true))
+                .arg(
+                    Arg::with_name("input")
+                        .short("i")
+                        .long("input")
+                        .takes_value(true)
+                        .help("Draws this file instead of the day's input"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short("o")
+                        .long("output")
+                        .takes_value(true)
+                        .help("Writes the drawing here instead of to stdout"),
+                )
+                .arg(param())
+                .args(&shorthands()),
         )
         .get_matches();
-    matches
-        .value_of("days")
-        .unwrap_or(&ALL)
-        .split(",")
-        .map(u8::from_str)
-        .map(Result::unwrap)
-        .map(|day| (day, run(day)))
-        .for_each(|(day, result)| {
-            if let Some(result) = result {
-                println!("Day {}", day);
-                println!("Part 1: {}", result[0]);
-                println!("Part 2: {}", result[1]);
+    if let Some(matches) = matches.subcommand_matches("new") {
+        let day = matches.value_of("day").unwrap();
+        let day = match u8::from_str(day) {
+            Ok(day) => day,
+            Err(_) => {
+                eprintln!("Couldn't scaffold day {}: Invalid day", day);
+                std::process::exit(1);
             }
-        });
+        };
+        if let Err(err) = scaffold::create(day) {
+            eprintln!("Couldn't scaffold day {}: {}", day, err);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("draw") {
+        if let Err(err) = draw(matches) {
+            eprintln!("Couldn't draw: {}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+    let format = matches
+        .value_of("format")
+        .map(Format::from_str)
+        .unwrap()
+        .unwrap();
+    let jobs = matches
+        .value_of("jobs")
+        .map(usize::from_str)
+        .unwrap()
+        .expect("Checked by the validator");
+    let days = matches
+        .value_of("days")
+        .unwrap_or(ALL)
+        .split(',')
+        .map(|day| day.parse().expect("Checked by the validator"))
+        .collect::<Vec<_>>();
+    let mut params = params(&matches);
+    render(&matches, format, &mut params);
+    let expected = matches
+        .values_of("expect")
+        .unwrap_or_default()
+        .map(|text| {
+            expectation(text).unwrap_or_else(|| {
+                eprintln!("Invalid expectation {:?}: expected day.part=answer", text);
+                std::process::exit(1);
+            })
+        })
+        .collect::<Vec<_>>();
+    let mut records = run_all(&days, &params, jobs);
+    let unmet = check(&mut records, &expected);
+    let stdout = std::io::stdout();
+    output::write(format, &records, &mut stdout.lock()).expect("Failed to write output");
+    if !unmet.is_empty() {
+        for problem in unmet {
+            eprintln!("{}", problem);
+        }
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn panics() {
+        let outcome = |f: fn()| panicked(panic::catch_unwind(f).unwrap_err());
+        assert_eq!(outcome(|| unimplemented!()), Outcome::Unimplemented);
+        assert_eq!(outcome(|| todo!()), Outcome::Unimplemented);
+        assert_eq!(
+            outcome(|| panic!("No parent!")),
+            Outcome::Failed("panicked: No parent!".to_string())
+        );
+        assert_eq!(
+            outcome(|| panic!("{} out of {}", 1, 2)),
+            Outcome::Failed("panicked: 1 out of 2".to_string())
+        );
+    }
+    #[test]
+    fn expectations() {
+        assert_eq!(
+            expectation("8.2=ZFLBY"),
+            Some((8, Part::Two, "ZFLBY".to_string()))
+        );
+        assert_eq!(expectation("8.3=ZFLBY"), None);
+        assert_eq!(expectation("8=ZFLBY"), None);
+        let record = |part, answer: i128| Record {
+            day: 1,
+            part,
+            outcome: Outcome::Solved(answer::Answer::Integer(answer)),
+            elapsed: std::time::Duration::default(),
+        };
+        let mut records = [record(Part::One, 12), record(Part::Two, 5)];
+        let expected = [
+            expectation("1.1=012").unwrap(),
+            expectation("1.2=6").unwrap(),
+            expectation("7.1=5").unwrap(),
+        ];
+        assert_eq!(
+            check(&mut records, &expected),
+            vec![
+                "Day 1 part 2: expected 6, got 5".to_string(),
+                "Day 7 part 1: day 7 wasn't run".to_string(),
+            ]
+        );
+        assert_eq!(
+            records[0].outcome,
+            Outcome::Solved(answer::Answer::Integer(12))
+        );
+        assert_eq!(
+            records[1].outcome,
+            Outcome::Failed("expected 6, got 5".to_string())
+        );
+        let mut records = [Record {
+            outcome: Outcome::Unimplemented,
+            ..record(Part::One, 0)
+        }];
+        assert_eq!(check(&mut records, &expected[..1]).len(), 1);
+        assert_eq!(
+            records[0].outcome,
+            Outcome::Failed("expected 012, but it isn't implemented".to_string())
+        );
+    }
 }
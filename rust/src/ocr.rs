@@ -0,0 +1,223 @@
+//! Reads the block letters that some puzzles draw their answers in.
+
+use crate::{error::Error, grid::Grid};
+
+/// A fixed-width font: each letter sits at the left of a cell `pitch` pixels wide. Glyphs are
+/// written a row at a time, top first, with spaces between the rows.
+struct Font {
+    height: usize,
+    pitch: usize,
+    glyphs: &'static [(char, &'static str)],
+}
+
+/// The font from 2016 day 8, 2019 days 8 and 11, and 2021 day 13. `Y` uses the whole cell.
+const SMALL: Font = Font {
+    height: 6,
+    pitch: 5,
+    glyphs: &[
+        ('A', ".##. #..# #..# #### #..# #..#"),
+        ('B', "###. #..# ###. #..# #..# ###."),
+        ('C', ".##. #..# #... #... #..# .##."),
+        ('E', "#### #... ###. #... #... ####"),
+        ('F', "#### #... ###. #... #... #..."),
+        ('G', ".##. #..# #... #.## #..# .###"),
+        ('H', "#..# #..# #### #..# #..# #..#"),
+        ('I', ".### ..#. ..#. ..#. ..#. .###"),
+        ('J', "..## ...# ...# ...# #..# .##."),
+        ('K', "#..# #.#. ##.. #.#. #.#. #..#"),
+        ('L', "#... #... #... #... #... ####"),
+        ('O', ".##. #..# #..# #..# #..# .##."),
+        ('P', "###. #..# #..# ###. #... #..."),
+        ('R', "###. #..# #..# ###. #.#. #..#"),
+        ('S', ".### #... #... .##. ...# ###."),
+        ('U', "#..# #..# #..# #..# #..# .##."),
+        ('Y', "#...# #...# .#.#. ..#.. ..#.. ..#.."),
+        ('Z', "#### ...# ..#. .#.. #... ####"),
+    ],
+};
+
+/// The font from 2018 day 10.
+const LARGE: Font = Font {
+    height: 10,
+    pitch: 8,
+    glyphs: &[
+        (
+            'A',
+            "..##.. .#..#. #....# #....# #....# ###### #....# #....# #....# #....#",
+        ),
+        (
+            'B',
+            "#####. #....# #....# #....# #####. #....# #....# #....# #....# #####.",
+        ),
+        (
+            'C',
+            ".####. #....# #..... #..... #..... #..... #..... #..... #....# .####.",
+        ),
+        (
+            'E',
+            "###### #..... #..... #..... #####. #..... #..... #..... #..... ######",
+        ),
+        (
+            'F',
+            "###### #..... #..... #..... #####. #..... #..... #..... #..... #.....",
+        ),
+        (
+            'G',
+            ".####. #....# #..... #..... #..... #..### #....# #....# #...## .###.#",
+        ),
+        (
+            'H',
+            "#....# #....# #....# #....# ###### #....# #....# #....# #....# #....#",
+        ),
+        (
+            'J',
+            "...### ....#. ....#. ....#. ....#. ....#. ....#. #...#. #...#. .###..",
+        ),
+        (
+            'K',
+            "#....# #...#. #..#.. #.#... ##.... ##.... #.#... #..#.. #...#. #....#",
+        ),
+        (
+            'L',
+            "#..... #..... #..... #..... #..... #..... #..... #..... #..... ######",
+        ),
+        (
+            'N',
+            "#....# ##...# ##...# #.#..# #.#..# #..#.# #..#.# #...## #...## #....#",
+        ),
+        (
+            'P',
+            "#####. #....# #....# #....# #####. #..... #..... #..... #..... #.....",
+        ),
+        (
+            'R',
+            "#####. #....# #....# #....# #####. #..#.. #...#. #...#. #....# #....#",
+        ),
+        (
+            'X',
+            "#....# #....# .#..#. .#..#. ..##.. ..##.. .#..#. .#..#. #....# #....#",
+        ),
+        (
+            'Z',
+            "###### .....# .....# ....#. ...#.. ..#... .#.... #..... #..... ######",
+        ),
+    ],
+};
+
+/// The letters read from a picture, with `?` for any that didn't match the font.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Reading {
+    pub(crate) text: String,
+    /// The column of the picture where each unknown letter starts.
+    pub(crate) unknown: Vec<usize>,
+}
+
+/// Reads the letters drawn in `lit` pixels, picking the font by the height of the picture.
+/// Cells line up with the left edge, as the puzzles draw them, so that letters with a blank
+/// first column like `I` stay in place. Blank cells at either end are dropped.
+pub(crate) fn read(lit: &Grid<bool>) -> Result<Reading, Error> {
+    let font = [SMALL, LARGE]
+        .iter()
+        .find(|font| font.height == lit.height())
+        .ok_or_else(|| {
+            Error::Unsupported(format!(
+                "There's no font {} pixels tall to read letters in (only 6 or 10)",
+                lit.height()
+            ))
+        })?;
+    let column_is_blank = |col: usize| lit.rows().all(|row| !row[col]);
+    let start = (0..lit.width())
+        .find(|&col| !column_is_blank(col))
+        .map_or(lit.width(), |col| col - col % font.pitch);
+    let end = (0..lit.width())
+        .rev()
+        .find(|&col| !column_is_blank(col))
+        .map_or(start, |col| col + 1);
+    let mut reading = Reading {
+        text: String::new(),
+        unknown: Vec::new(),
+    };
+    for left in (start..end).step_by(font.pitch) {
+        let cell = |row: usize, col: usize| left + col < lit.width() && lit[(row, left + col)];
+        let matches = |glyph: &str| {
+            glyph.split(' ').enumerate().all(|(row, pattern)| {
+                (0..font.pitch).all(|col| {
+                    let on = pattern.as_bytes().get(col) == Some(&b'#');
+                    cell(row, col) == on
+                })
+            })
+        };
+        match font.glyphs.iter().find(|(_, glyph)| matches(glyph)) {
+            Some(&(letter, _)) => reading.text.push(letter),
+            None => {
+                reading.text.push('?');
+                reading.unknown.push(left);
+            }
+        }
+    }
+    Ok(reading)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn picture(rows: &[&str]) -> Grid<bool> {
+        let cells = rows
+            .iter()
+            .flat_map(|row| row.chars().map(|c| c == '#'))
+            .collect();
+        Grid::from_cells(rows[0].len(), cells).unwrap()
+    }
+    #[test]
+    fn small() {
+        let lit = picture(&[
+            "####.####.#....###..#...#",
+            "...#.#....#....#..#.#...#",
+            "..#..###..#....###...#.#.",
+            ".#...#....#....#..#...#..",
+            "#....#....#....#..#...#..",
+            "####.#....####.###....#..",
+        ]);
+        let reading = read(&lit).unwrap();
+        assert_eq!(reading.text, "ZFLBY");
+        assert!(reading.unknown.is_empty());
+        // `I` starts with a blank column, which mustn't push the cells along.
+        let lit = picture(&[
+            ".###.#..#.",
+            "..#..#..#.",
+            "..#..####.",
+            "..#..#..#.",
+            "..#..#..#.",
+            ".###.#..#.",
+        ]);
+        assert_eq!(read(&lit).unwrap().text, "IH");
+    }
+    #[test]
+    fn large() {
+        let mut rows = vec![String::new(); 10];
+        for letter in ['H', 'X'] {
+            let (_, glyph) = LARGE.glyphs.iter().find(|(c, _)| *c == letter).unwrap();
+            for (row, pattern) in rows.iter_mut().zip(glyph.split(' ')) {
+                row.push_str(pattern);
+                row.push_str("..");
+            }
+        }
+        let rows = rows.iter().map(String::as_str).collect::<Vec<_>>();
+        assert_eq!(read(&picture(&rows)).unwrap().text, "HX");
+    }
+    #[test]
+    fn unknown() {
+        let lit = picture(&[
+            "#..#.#...",
+            "#..#.#.#.",
+            "####.#...",
+            "#..#.#.#.",
+            "#..#.#...",
+            "#..#.####",
+        ]);
+        let reading = read(&lit).unwrap();
+        assert_eq!(reading.text, "H?");
+        assert_eq!(reading.unknown, [5]);
+        assert!(read(&picture(&["#"])).is_err());
+    }
+}
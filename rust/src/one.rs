@@ -1,57 +1,168 @@
-use std::str::FromStr;
-
-fn fuel(mass: u32) -> u32 {
-    (mass / 3).saturating_sub(2)
-}
-
-fn part1(modules: &[u32]) -> u32 {
-    modules.iter().copied().map(fuel).sum()
-}
-
-fn part2(modules: &[u32]) -> u32 {
-    modules
-        .iter()
-        .copied()
-        .map(|module| {
-            (1..)
-                .scan(module, |state, _| {
-                    let x = fuel(*state);
-                    *state = x;
-                    if x > 0 {
-                        Some(x)
-                    } else {
-                        None
-                    }
-                })
-                .sum::<u32>()
+use crate::{
+    answer::Answer,
+    digits::Unsigned,
+    error::{self, Error, ParseError},
+    params::Params,
+    Part,
+};
+use fuel::Breakdown;
+use std::{any::type_name, convert::TryInto, fmt::Display, str::FromStr};
+
+mod fuel;
+
+/// A type that module masses can be stored in.
+trait Mass: Unsigned + Display + FromStr + TryInto<i128> {}
+
+impl Mass for u32 {}
+impl Mass for u64 {}
+impl Mass for u128 {}
+
+/// Adds up the fuel, unless the total won't fit in `T`.
+fn sum<T: Mass>(mut fuel: impl Iterator<Item = T>) -> Result<T, Error> {
+    fuel.try_fold(T::from(0), |total, fuel| total.checked_add(fuel))
+        .ok_or_else(|| {
+            Error::Overflow(format!(
+                "the fuel for all the modules doesn't fit in {}",
+                type_name::<T>()
+            ))
         })
-        .sum()
 }
 
-pub(crate) fn run(input: String) -> [String; 2] {
-    let modules = input
-        .lines()
-        .filter_map(|x| u32::from_str(x).ok())
-        .collect::<Vec<_>>();
-    let part1 = format!("{}", part1(&modules));
-    let part2 = format!("{}", part2(&modules));
-    [part1, part2]
+fn part1<T: Mass>(modules: &[Breakdown<T>]) -> Result<T, Error> {
+    sum(modules.iter().map(|module| module.direct))
+}
+
+fn part2<T: Mass>(modules: &[Breakdown<T>]) -> Result<T, Error> {
+    sum(modules.iter().map(Breakdown::total))
+}
+
+/// Lays out each module's fuel in a table.
+fn report<T: Mass>(modules: &[Breakdown<T>]) -> String {
+    let mut rows = vec![[
+        "mass".to_string(),
+        "direct fuel".to_string(),
+        "fuel for fuel".to_string(),
+        "rounds".to_string(),
+    ]];
+    rows.extend(modules.iter().map(|module| {
+        [
+            module.mass.to_string(),
+            module.direct.to_string(),
+            module.extra.to_string(),
+            module.rounds.to_string(),
+        ]
+    }));
+    let mut widths = [0; 4];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = cell.len().max(*width);
+        }
+    }
+    let lines = rows.iter().map(|row| {
+        let cells = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{:>width$}", cell, width = width));
+        cells.collect::<Vec<_>>().join("  ")
+    });
+    lines.collect::<Vec<_>>().join("\n")
+}
+
+/// Masses are `u64`s unless `masses` says to use `u32` or `u128` instead. `mass=12,1969`
+/// works out the fuel for those masses rather than the input's, and `breakdown=true` shows
+/// the fuel for each module as well as the total.
+pub(crate) fn run(input: &str, part: Part, params: &Params) -> Result<Answer, Error> {
+    match params.choice("masses", &["u32", "u64", "u128"])? {
+        Some("u32") => solve::<u32>(input, part, params),
+        Some("u128") => solve::<u128>(input, part, params),
+        _ => solve::<u64>(input, part, params),
+    }
+}
+
+fn parse<T: Mass>(input: &str) -> Result<Vec<T>, ParseError> {
+    let expected = format!("a module mass that fits in {}", type_name::<T>());
+    error::lines(input)
+        .map(|(line, text)| {
+            text.trim()
+                .parse::<T>()
+                .map_err(|_| ParseError::new(line, 1, text, &expected))
+        })
+        .collect()
+}
+
+fn solve<T: Mass>(input: &str, part: Part, params: &Params) -> Result<Answer, Error> {
+    let masses = match params.get::<String>("mass")? {
+        Some(masses) => {
+            let expected = format!("a mass that fits in {}", type_name::<T>());
+            error::numbers(1, &masses, ',', &expected).map_err(|err| {
+                Error::Unsupported(format!("Invalid mass {:?}: expected {}", err.text, expected))
+            })?
+        }
+        None => parse::<T>(input)?,
+    };
+    let modules = masses.into_iter().map(Breakdown::new).collect::<Vec<_>>();
+    let answer = match part {
+        Part::One => part1(&modules)?,
+        Part::Two => part2(&modules)?,
+    };
+    let answer = Answer::integer(answer)?;
+    match params.get_or("breakdown", false)? {
+        true => Ok(answer.with_detail(report(&modules))),
+        false => Ok(answer),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    fn modules<T: Mass>(masses: &[T]) -> Vec<Breakdown<T>> {
+        masses.iter().copied().map(Breakdown::new).collect()
+    }
     #[test]
     fn simple() {
-        assert_eq!(part1(&[12]), 2);
-        assert_eq!(part1(&[14]), 2);
-        assert_eq!(part1(&[1969]), 654);
-        assert_eq!(part1(&[100756]), 33583);
+        assert_eq!(part1(&modules(&[12u32])).unwrap(), 2);
+        assert_eq!(part1(&modules(&[14u32])).unwrap(), 2);
+        assert_eq!(part1(&modules(&[1969u32])).unwrap(), 654);
+        assert_eq!(part1(&modules(&[100756u32])).unwrap(), 33583);
     }
     #[test]
     fn recursive() {
-        assert_eq!(part2(&[14]), 2);
-        assert_eq!(part2(&[1969]), 966);
-        assert_eq!(part2(&[100756]), 50346);
+        assert_eq!(part2(&modules(&[14u32])).unwrap(), 2);
+        assert_eq!(part2(&modules(&[1969u32])).unwrap(), 966);
+        assert_eq!(part2(&modules(&[100756u32])).unwrap(), 50346);
+    }
+    #[test]
+    fn wide() {
+        let modules = modules(&[u32::MAX; 4]);
+        assert_eq!(
+            part1(&modules).unwrap_err().to_string(),
+            "Overflow: the fuel for all the modules doesn't fit in u32"
+        );
+        let params = "masses=u128 mass=340282366920938463463374607431768211455"
+            .parse::<Params>()
+            .unwrap();
+        assert_eq!(
+            run("", Part::One, &params).unwrap(),
+            Answer::Integer(113427455640312821154458202477256070483)
+        );
+    }
+    #[test]
+    fn breakdown() {
+        let params = "mass=14,1969 breakdown=true".parse::<Params>().unwrap();
+        let answer = run("", Part::Two, &params).unwrap();
+        assert_eq!(answer.plain(), &Answer::Integer(968));
+        assert_eq!(
+            answer.described(),
+            "\
+968
+mass  direct fuel  fuel for fuel  rounds
+  14            2              0       1
+1969          654            312       5"
+        );
+        let params = "mass=14,x".parse::<Params>().unwrap();
+        assert_eq!(
+            run("", Part::One, &params).unwrap_err().to_string(),
+            "Invalid mass \"x\": expected a mass that fits in u64"
+        );
     }
 }
@@ -0,0 +1,77 @@
+use crate::digits::Unsigned;
+
+/// The fuel to launch `mass` on its own: a third of it, rounded down, less 2, and never less
+/// than nothing.
+pub(super) fn fuel<T: Unsigned>(mass: T) -> T {
+    let third = mass / T::from(3);
+    if third > T::from(2) {
+        third - T::from(2)
+    } else {
+        T::from(0)
+    }
+}
+
+/// The fuel for one module, counting the fuel that its fuel needs in turn.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) struct Breakdown<T> {
+    pub(super) mass: T,
+    /// The fuel for the module's own mass.
+    pub(super) direct: T,
+    /// The fuel for the direct fuel, the fuel for that, and so on.
+    pub(super) extra: T,
+    /// How many rounds of fuel there were, the direct fuel included.
+    pub(super) rounds: u32,
+}
+
+impl<T: Unsigned> Breakdown<T> {
+    /// Each round needs less than a third of the fuel of the last, so there are only about
+    /// log3 of the mass of them: at most 39 for a `u64` and 79 for a `u128`.
+    pub(super) fn new(mass: T) -> Self {
+        let zero = T::from(0);
+        let direct = fuel(mass);
+        let (mut last, mut extra, mut rounds) = (direct, zero, 0);
+        while last > zero {
+            rounds += 1;
+            last = fuel(last);
+            // The fuel all together is less than half the mass, so this can't overflow.
+            extra = extra.checked_add(last).expect("Fuel is lighter than its mass");
+        }
+        Self {
+            mass,
+            direct,
+            extra,
+            rounds,
+        }
+    }
+    /// All the fuel the module needs.
+    pub(super) fn total(&self) -> T {
+        self.direct
+            .checked_add(self.extra)
+            .expect("Fuel is lighter than its mass")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn breakdowns() {
+        assert_eq!(fuel(1969u32), 654);
+        assert_eq!(fuel(8u64), 0);
+        assert_eq!(
+            Breakdown::new(1969u32),
+            Breakdown {
+                mass: 1969,
+                direct: 654,
+                extra: 312,
+                rounds: 5,
+            }
+        );
+        assert_eq!(Breakdown::new(100756u64).total(), 50346);
+        assert_eq!(Breakdown::new(8u8).rounds, 0);
+        assert_eq!(Breakdown::new(u64::MAX).rounds, 39);
+        let widest = Breakdown::new(u128::MAX);
+        assert!(widest.total() < u128::MAX / 2);
+        assert_eq!(widest.rounds, 79);
+    }
+}
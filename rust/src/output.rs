@@ -0,0 +1,259 @@
+use crate::{answer::Answer, Part};
+use std::{io::Write, str::FromStr, time::Duration};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(format!("Unknown output format: {}", s)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Outcome {
+    Solved(Answer),
+    Failed(String),
+    Unimplemented,
+}
+
+impl Outcome {
+    fn status(&self) -> &'static str {
+        match self {
+            Self::Solved(_) => "ok",
+            Self::Failed(_) => "error",
+            Self::Unimplemented => "unimplemented",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Record {
+    pub(crate) day: u8,
+    pub(crate) part: Part,
+    pub(crate) outcome: Outcome,
+    pub(crate) elapsed: Duration,
+}
+
+impl Record {
+    fn millis(&self) -> f64 {
+        self.elapsed.as_secs_f64() * 1000.0
+    }
+}
+
+fn escape_json(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn escape_csv(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn write_text<W: Write>(records: &[Record], out: &mut W) -> std::io::Result<()> {
+    let mut day = None;
+    for record in records {
+        let text = match &record.outcome {
+            // Pictures start on a line of their own so that their rows line up.
+            Outcome::Solved(answer @ Answer::Image(_)) => format!("\n{}", answer),
+            Outcome::Solved(answer) => format!(" {}", answer.described()),
+            Outcome::Failed(err) => format!(" error: {}", err),
+            // Unsolved days aren't worth cluttering the terminal with.
+            Outcome::Unimplemented => continue,
+        };
+        if day != Some(record.day) {
+            writeln!(out, "Day {}", record.day)?;
+            day = Some(record.day);
+        }
+        writeln!(out, "Part {}:{}", record.part, text)?;
+    }
+    Ok(())
+}
+
+fn write_json<W: Write>(records: &[Record], out: &mut W) -> std::io::Result<()> {
+    writeln!(out, "[")?;
+    for (i, record) in records.iter().enumerate() {
+        let null = || "null".to_string();
+        let (kind, answer, detail, error) = match &record.outcome {
+            Outcome::Solved(answer) => {
+                let value = match answer.plain() {
+                    Answer::Integer(n) => n.to_string(),
+                    Answer::Text(text) | Answer::Image(text) => escape_json(text),
+                    Answer::None(_) | Answer::Detailed(..) => null(),
+                };
+                let detail = answer.detail().map_or_else(null, escape_json);
+                (escape_json(answer.kind()), value, detail, null())
+            }
+            Outcome::Failed(err) => (null(), null(), null(), escape_json(err)),
+            Outcome::Unimplemented => (null(), null(), null(), null()),
+        };
+        let reason = match &record.outcome {
+            Outcome::Solved(answer) => match answer.plain() {
+                Answer::None(reason) => escape_json(reason),
+                _ => null(),
+            },
+            _ => null(),
+        };
+        write!(
+            out,
+            "  {{\"day\": {}, \"part\": {}, \"status\": \"{}\", \"type\": {}, \"answer\": {}, \"reason\": {}, \"detail\": {}, \"error\": {}, \"time_ms\": {:.3}}}",
+            record.day,
+            record.part,
+            record.outcome.status(),
+            kind,
+            answer,
+            reason,
+            detail,
+            error,
+            record.millis()
+        )?;
+        writeln!(out, "{}", if i + 1 < records.len() { "," } else { "" })?;
+    }
+    writeln!(out, "]")
+}
+
+fn write_csv<W: Write>(records: &[Record], out: &mut W) -> std::io::Result<()> {
+    writeln!(
+        out,
+        "day,part,status,type,answer,reason,detail,error,time_ms"
+    )?;
+    for record in records {
+        // Each field, escaped, or left empty when it doesn't apply.
+        let (mut kind, mut answer, mut reason, mut detail, mut error) = Default::default();
+        match &record.outcome {
+            Outcome::Solved(solved) => {
+                kind = solved.kind();
+                match solved.plain() {
+                    Answer::None(why) => reason = escape_csv(why),
+                    plain => answer = escape_csv(&plain.to_string()),
+                }
+                detail = solved.detail().map(escape_csv).unwrap_or_default();
+            }
+            Outcome::Failed(err) => error = escape_csv(err),
+            Outcome::Unimplemented => {}
+        }
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{:.3}",
+            record.day,
+            record.part,
+            record.outcome.status(),
+            kind,
+            answer,
+            reason,
+            detail,
+            error,
+            record.millis()
+        )?;
+    }
+    Ok(())
+}
+
+pub(crate) fn write<W: Write>(
+    format: Format,
+    records: &[Record],
+    out: &mut W,
+) -> std::io::Result<()> {
+    match format {
+        Format::Text => write_text(records, out),
+        Format::Json => write_json(records, out),
+        Format::Csv => write_csv(records, out),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn record(outcome: Outcome) -> Record {
+        Record {
+            day: 8,
+            part: Part::Two,
+            outcome,
+            elapsed: Duration::from_millis(2),
+        }
+    }
+    #[test]
+    fn json_escapes() {
+        assert_eq!(escape_json("plain"), "\"plain\"");
+        assert_eq!(escape_json("a\nb"), "\"a\\nb\"");
+        assert_eq!(escape_json("\"q\" \\"), "\"\\\"q\\\" \\\\\"");
+        assert_eq!(escape_json("\u{1b}"), "\"\\u001b\"");
+    }
+    #[test]
+    fn csv_escapes() {
+        assert_eq!(escape_csv("42"), "42");
+        assert_eq!(escape_csv("a,b"), "\"a,b\"");
+        assert_eq!(escape_csv("say \"hi\"\n"), "\"say \"\"hi\"\"\n\"");
+    }
+    #[test]
+    fn formats() {
+        let records = [
+            record(Outcome::Solved(Answer::image("#.\n.#"))),
+            record(Outcome::Solved(Answer::Integer(-7))),
+            record(Outcome::Solved(Answer::None("no luck".to_string()))),
+            record(Outcome::Unimplemented),
+            record(Outcome::Failed("bad, input".to_string())),
+            record(Outcome::Solved(
+                Answer::Integer(6).with_detail("at (3, 3), twice".to_string()),
+            )),
+        ];
+        let mut out = Vec::new();
+        write(Format::Json, &records, &mut out).unwrap();
+        let json = String::from_utf8(out).unwrap();
+        assert!(json.contains("\"type\": \"image\", \"answer\": \"#.\\n.#\""));
+        assert!(json.contains("\"type\": \"integer\", \"answer\": -7,"));
+        assert!(json.contains("\"answer\": null, \"reason\": \"no luck\""));
+        assert!(json.contains("\"status\": \"unimplemented\", \"type\": null"));
+        assert!(json.contains(
+            "\"answer\": null, \"reason\": null, \"detail\": null, \"error\": \"bad, input\""
+        ));
+        assert!(json.contains("\"answer\": 6, \"reason\": null, \"detail\": \"at (3, 3), twice\""));
+        let mut out = Vec::new();
+        write(Format::Csv, &records, &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        assert_eq!(
+            csv,
+            "day,part,status,type,answer,reason,detail,error,time_ms\n\
+             8,2,ok,image,\"#.\n.#\",,,,2.000\n\
+             8,2,ok,integer,-7,,,,2.000\n\
+             8,2,ok,none,,no luck,,,2.000\n\
+             8,2,unimplemented,,,,,,2.000\n\
+             8,2,error,,,,,\"bad, input\",2.000\n\
+             8,2,ok,integer,6,,\"at (3, 3), twice\",,2.000\n"
+        );
+        let mut out = Vec::new();
+        write(Format::Text, &records, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Day 8\nPart 2:\n#.\n.#\nPart 2: -7\nPart 2: no answer: no luck\nPart 2: error: bad, input\n\
+             Part 2: 6 at (3, 3), twice\n"
+        );
+    }
+}
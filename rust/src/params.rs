@@ -0,0 +1,113 @@
+use crate::error::Error;
+use std::{collections::HashMap, str::FromStr};
+
+/// Optional `key=value` settings that tweak how a day is solved, such as the image size for
+/// day 8 or the target output for day 2.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct Params {
+    values: HashMap<String, String>,
+}
+
+impl Params {
+    pub(crate) fn insert(&mut self, key: &str, value: &str) {
+        self.values.insert(key.to_string(), value.to_string());
+    }
+    pub(crate) fn contains(&self, key: &str) -> bool {
+        self.values.contains_key(key)
+    }
+    /// The value of `key`, or an error naming the key if it isn't a `T`.
+    pub(crate) fn get<T: FromStr>(&self, key: &str) -> Result<Option<T>, Error> {
+        match self.values.get(key) {
+            Some(value) => value
+                .parse()
+                .map(Some)
+                .map_err(|_| Error::Unsupported(format!("Invalid {} {:?}", key, value))),
+            None => Ok(None),
+        }
+    }
+    pub(crate) fn get_or<T: FromStr>(&self, key: &str, default: T) -> Result<T, Error> {
+        Ok(self.get(key)?.unwrap_or(default))
+    }
+    /// The value of `key`, which has to be one of `choices`.
+    pub(crate) fn choice(
+        &self,
+        key: &str,
+        choices: &[&'static str],
+    ) -> Result<Option<&'static str>, Error> {
+        let value = match self.values.get(key) {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        match choices.iter().find(|&&choice| choice == value) {
+            Some(&choice) => Ok(Some(choice)),
+            None => {
+                let (last, rest) = choices.split_last().expect("There are choices");
+                Err(Error::Unsupported(format!(
+                    "Invalid {} {:?} (try {} or {})",
+                    key,
+                    value,
+                    rest.join(", "),
+                    last
+                )))
+            }
+        }
+    }
+}
+
+impl FromStr for Params {
+    type Err = String;
+    /// Parses whitespace-separated `key=value` pairs.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut params = Self::default();
+        for (i, pair) in s.split_whitespace().enumerate() {
+            let mut split = pair.splitn(2, '=');
+            match (split.next(), split.next()) {
+                (Some(key), Some(value)) if !key.is_empty() => params.insert(key, value),
+                // Most likely the rest of the value before, split off at a space.
+                _ if i > 0 => {
+                    return Err(format!(
+                        "Expected key=value, found {} (values can't have spaces)",
+                        pair
+                    ))
+                }
+                _ => return Err(format!("Expected key=value, found {}", pair)),
+            }
+        }
+        Ok(params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parse() {
+        let params = "width=3 height=2 name=a=b".parse::<Params>().unwrap();
+        assert_eq!(params.get::<usize>("width").unwrap(), Some(3));
+        assert_eq!(params.get_or::<usize>("height", 6).unwrap(), 2);
+        assert_eq!(params.get_or::<usize>("depth", 6).unwrap(), 6);
+        assert_eq!(
+            params.get::<String>("name").unwrap().as_deref(),
+            Some("a=b")
+        );
+        assert_eq!(
+            params.get::<u8>("name").unwrap_err().to_string(),
+            "Invalid name \"a=b\""
+        );
+        assert_eq!(params.choice("width", &["2", "3"]).unwrap(), Some("3"));
+        assert_eq!(params.choice("depth", &["2", "3"]).unwrap(), None);
+        assert_eq!(
+            params
+                .choice("width", &["1", "2", "4"])
+                .unwrap_err()
+                .to_string(),
+            "Invalid width \"3\" (try 1, 2 or 4)"
+        );
+        assert!("width".parse::<Params>().is_err());
+        assert!("=3".parse::<Params>().is_err());
+        assert_eq!(
+            "rule=length(6) & increasing".parse::<Params>(),
+            Err("Expected key=value, found & (values can't have spaces)".to_string())
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+};
+
+const ONES: [&str; 20] = [
+    "",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TEMPLATE: &str = r#"use crate::{answer::Answer, error::Error, params::Params, Part};
+
+pub(crate) fn run(input: &str, part: Part, _params: &Params) -> Result<Answer, Error> {
+    Ok(match part {
+        Part::One => part1(input),
+        Part::Two => part2(input),
+    })
+}
+
+fn part1(_input: &str) -> Answer {
+    unimplemented!()
+}
+
+fn part2(_input: &str) -> Answer {
+    unimplemented!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    #[ignore]
+    fn example() {
+        assert_eq!(run("", Part::One, &Params::default()).unwrap(), Answer::Integer(0));
+    }
+}
+"#;
+
+/// The module name for a day, e.g. `twenty_one` for day 21.
+fn name(day: u8) -> Option<String> {
+    match day {
+        1..=19 => Some(ONES[day as usize].to_string()),
+        20 => Some("twenty".to_string()),
+        21..=25 => Some(format!("twenty_{}", ONES[day as usize - 20])),
+        _ => None,
+    }
+}
+
+/// Adds a day to the `days!` invocation in `main.rs`, keeping the entries in order.
+fn register(main: &str, day: u8, name: &str) -> Option<String> {
+    let start = main.find("days! {\n")? + "days! {\n".len();
+    let end = start + main[start..].find("\n}")? + 1;
+    let mut entries = main[start..end]
+        .lines()
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>();
+    let number = |line: &str| line.trim().split(' ').next()?.parse::<u8>().ok();
+    if entries.iter().any(|line| number(line) == Some(day)) {
+        return None;
+    }
+    let index = entries
+        .iter()
+        .position(|line| number(line).is_some_and(|d| d > day))
+        .unwrap_or(entries.len());
+    entries.insert(index, format!("    {} => {},", day, name));
+    let mut registered = main[..start].to_string();
+    for entry in entries {
+        registered.push_str(&entry);
+        registered.push('\n');
+    }
+    registered.push_str(&main[end..]);
+    Some(registered)
+}
+
+fn error(kind: io::ErrorKind, message: String) -> io::Error {
+    io::Error::new(kind, message)
+}
+
+/// Writes `src/<name>.rs` from the template, registers it in `src/main.rs` and creates an empty
+/// `../inputs/<day>.txt` if there isn't one already.
+pub(crate) fn create(day: u8) -> io::Result<()> {
+    let name = name(day).ok_or_else(|| {
+        error(
+            io::ErrorKind::InvalidInput,
+            format!("There is no day {}", day),
+        )
+    })?;
+    let main = fs::read_to_string("src/main.rs")?;
+    let main = register(&main, day, &name).ok_or_else(|| {
+        error(
+            io::ErrorKind::AlreadyExists,
+            format!("Day {} is already registered", day),
+        )
+    })?;
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(format!("src/{}.rs", name))?
+        .write_all(TEMPLATE.as_bytes())?;
+    fs::write("src/main.rs", main)?;
+    let input = format!("../inputs/{}.txt", day);
+    if fs::metadata(&input).is_err() {
+        fs::write(input, "")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn names() {
+        assert_eq!(name(9).as_deref(), Some("nine"));
+        assert_eq!(name(20).as_deref(), Some("twenty"));
+        assert_eq!(name(25).as_deref(), Some("twenty_five"));
+        assert_eq!(name(0), None);
+        assert_eq!(name(26), None);
+    }
+    #[test]
+    fn registration() {
+        let main = "days! {\n    1 => one,\n    3 => three,\n}\n\nfn main() {}\n";
+        assert_eq!(
+            register(main, 2, "two").unwrap(),
+            "days! {\n    1 => one,\n    2 => two,\n    3 => three,\n}\n\nfn main() {}\n"
+        );
+        assert_eq!(
+            register(main, 9, "nine").unwrap(),
+            "days! {\n    1 => one,\n    3 => three,\n    9 => nine,\n}\n\nfn main() {}\n"
+        );
+        assert_eq!(register(main, 3, "three"), None);
+    }
+}
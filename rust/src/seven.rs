@@ -1,3 +1,5 @@
-pub(crate) fn run(_input: String) -> [String; 2] {
+use crate::{answer::Answer, error::Error, params::Params, Part};
+
+pub(crate) fn run(_input: &str, _part: Part, _params: &Params) -> Result<Answer, Error> {
     unimplemented!()
 }
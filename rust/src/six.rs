@@ -1,97 +1,464 @@
-use std::{collections::HashMap, iter::FromIterator, str::FromStr};
-
-pub(crate) fn run(input: String) -> [String; 2] {
-    let planets = input
-        .lines()
-        .filter_map(|x| x.parse::<Pair>().ok())
-        .collect::<Planets>();
-    let total = planets.total_orbits();
-    let total = format!("{}", total);
-    let path1 = planets.path(&"YOU".parse().unwrap(), &"COM".parse().unwrap());
-    let path2 = planets.path(&"SAN".parse().unwrap(), &"COM".parse().unwrap());
-    let common = path1
-        .iter()
-        .find(|x| path2.contains(x))
-        .expect("No intersection.");
-    let dist = path1.iter().take_while(|x| x != &common).count()
-        + path2.iter().take_while(|x| x != &common).count();
-    let dist = format!("{}", dist);
-    [total, dist]
-}
-
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
-struct Planet {
-    name: String,
-}
-
-impl FromStr for Planet {
-    type Err = std::convert::Infallible;
-    fn from_str(name: &str) -> Result<Self, Self::Err> {
-        Ok(Self {
-            name: name.to_string(),
+use crate::{
+    answer::Answer,
+    error::{self, Error, ParseError},
+    params::Params,
+    Part,
+};
+use std::{collections::HashMap, fmt, iter::FromIterator, str::FromStr};
+
+mod render;
+
+fn parse(input: &str) -> Result<Planets, ParseError> {
+    error::lines(input)
+        .map(|(line, text)| {
+            text.trim()
+                .parse::<Pair>()
+                .map(|pair| (line, pair))
+                .map_err(|expected| ParseError::new(line, 1, text, expected))
         })
+        .collect()
+}
+
+pub(crate) fn run(input: &str, part: Part, params: &Params) -> Result<Answer, Error> {
+    let planets = parse(input)?;
+    planets.validate().map_err(invalid)?;
+    match part {
+        Part::One => Ok(planets.total_orbits().into()),
+        Part::Two => transfers(
+            &planets,
+            &params.get_or("from", "YOU".to_string())?,
+            &params.get_or("to", "SAN".to_string())?,
+        ),
     }
 }
 
+fn invalid(problems: Vec<Problem>) -> Error {
+    Error::Invalid {
+        what: "orbit map".to_string(),
+        problems: problems.iter().map(ToString::to_string).collect(),
+    }
+}
+
+/// A body that was asked about but isn't in the map.
+fn missing(name: &str) -> Error {
+    Error::Unsupported(format!("There's no {} in the map", name))
+}
+
+/// Counts the orbital transfers needed to get from the body `from` orbits to the one `to` orbits.
+fn transfers(planets: &Planets, from: &str, to: &str) -> Result<Answer, Error> {
+    let center = |name: &str| {
+        let body = planets.id(name).ok_or_else(|| missing(name))?;
+        planets.parents[body]
+            .ok_or_else(|| Error::Unsupported(format!("{} doesn't orbit anything", name)))
+    };
+    let distance = planets.distance(center(from)?, center(to)?).ok_or_else(|| {
+        Error::Unsupported(format!("{} and {} aren't in the same system", from, to))
+    })?;
+    Ok(distance.into())
+}
+
+/// Draws the orbit map as a Graphviz `dot` graph or an indented `tree`, with the path between
+/// `from` and `to` (YOU and SAN unless given) picked out. `root=NAME` draws only what orbits
+/// that body, and `depth=N` stops N levels below it.
+pub(crate) fn draw(input: &str, format: &str, params: &Params) -> Result<Vec<u8>, Error> {
+    let planets = parse(input)?;
+    planets.validate().map_err(invalid)?;
+    let root = match params.get::<String>("root")? {
+        Some(name) => planets.id(&name).ok_or_else(|| missing(&name))?,
+        None => match planets.root() {
+            Some(root) => root,
+            None => return Ok(Vec::new()),
+        },
+    };
+    // The default path is only drawn if it's there, but one that was asked for has to be.
+    let end = |key: &str, default: &str| match params.get::<String>(key)? {
+        Some(name) => planets.id(&name).map(Some).ok_or_else(|| missing(&name)),
+        None => Ok(planets.id(default)),
+    };
+    let path = match (end("from", "YOU")?, end("to", "SAN")?) {
+        (Some(from), Some(to)) => planets.path(from, to).unwrap_or_default(),
+        _ => Vec::new(),
+    };
+    let view = render::View {
+        root,
+        depth: params.get("depth")?,
+        path,
+    };
+    let drawing = match format {
+        "dot" => render::dot(&planets, &view),
+        "tree" => render::tree(&planets, &view),
+        _ => {
+            return Err(Error::Unsupported(format!(
+                "Day 6 can't be drawn as {} (try dot or tree)",
+                format
+            )))
+        }
+    };
+    Ok(drawing.into_bytes())
+}
+
+/// Why an orbit map that parses still isn't a single tree of orbits.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Problem {
+    /// A body is listed as orbiting more than one center, on these lines.
+    Centers { body: String, lines: Vec<usize> },
+    /// These bodies orbit each other in a loop, set up on these lines.
+    Cycle { bodies: Vec<String>, lines: Vec<usize> },
+    /// The bodies fall into separate trees, around these roots, first seen on these lines.
+    Disconnected { roots: Vec<(String, usize)> },
+}
+
+/// Lists things as "a", "a and b" or "a, b and c".
+fn list<T: fmt::Display>(items: &[T]) -> String {
+    let items = items.iter().map(T::to_string).collect::<Vec<_>>();
+    match items.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        _ => items.concat(),
+    }
+}
+
+fn lines(lines: &[usize]) -> String {
+    match lines {
+        [line] => format!("line {}", line),
+        lines => format!("lines {}", list(lines)),
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Centers { body, lines: at } => {
+                write!(f, "{} orbits more than one body ({})", body, lines(at))
+            }
+            Self::Cycle { bodies, lines: at } => {
+                write!(f, "{} orbit each other in a loop ({})", list(bodies), lines(at))
+            }
+            Self::Disconnected { roots } => {
+                let roots = roots
+                    .iter()
+                    .map(|(root, line)| format!("{} (line {})", root, line))
+                    .collect::<Vec<_>>();
+                write!(
+                    f,
+                    "the map splits into {} systems, around {}",
+                    roots.len(),
+                    list(&roots)
+                )
+            }
+        }
+    }
+}
+
+/// A body in the orbit map, as an index into the map's tables.
+type Body = usize;
+
 #[derive(Debug, Default)]
 struct Planets {
-    map: HashMap<Planet, Planet>,
+    names: Vec<String>,
+    ids: HashMap<String, Body>,
+    /// The first line each body appears on.
+    first_lines: Vec<usize>,
+    /// Every line giving what each body orbits, along with the center it gives.
+    listings: Vec<Vec<(usize, Body)>>,
+    /// What each body orbits, if anything. The last line listing it wins.
+    parents: Vec<Option<Body>>,
+    /// The bodies orbiting each one directly.
+    children: Vec<Vec<Body>>,
+    /// How many bodies each one orbits, directly or indirectly.
+    depths: Vec<u32>,
+    /// `jumps[k][body]` is what `body` orbits 2^k levels further in, or the root of its tree
+    /// if there aren't that many levels.
+    jumps: Vec<Vec<Body>>,
+    /// How many bodies are in the subtree under each one, counting itself.
+    sizes: Vec<usize>,
 }
 
 impl Planets {
-    fn orbits(&self, planet: &Planet) -> u32 {
-        if let Some(center) = self.map.get(planet) {
-            1 + self.orbits(&center)
-        } else {
-            0
-        }
-    }
-    fn total_orbits(&self) -> u32 {
-        self.map.keys().map(|x| self.orbits(x)).sum()
-    }
-    fn path(&self, from: &Planet, to: &Planet) -> Vec<Planet> {
-        let mut path = Vec::new();
-        let mut planet = from;
-        loop {
-            if let Some(p) = self.map.get(planet) {
-                planet = p;
-                if planet == to {
-                    break;
-                }
-                path.push(planet.clone());
-            } else {
-                panic!("No parent!");
+    fn intern(&mut self, name: String, line: usize) -> Body {
+        if let Some(&body) = self.ids.get(&name) {
+            return body;
+        }
+        let body = self.names.len();
+        self.names.push(name.clone());
+        self.ids.insert(name, body);
+        self.first_lines.push(line);
+        self.listings.push(Vec::new());
+        self.parents.push(None);
+        self.children.push(Vec::new());
+        body
+    }
+    fn id(&self, name: &str) -> Option<Body> {
+        self.ids.get(name).copied()
+    }
+    /// Every body that orbits nothing, then the ones orbiting those, and so on, so that each
+    /// body comes after everything it orbits.
+    fn order(&self) -> Vec<Body> {
+        let mut order = (0..self.names.len())
+            .filter(|&body| self.parents[body].is_none())
+            .collect::<Vec<_>>();
+        let mut i = 0;
+        while let Some(&body) = order.get(i) {
+            order.extend(&self.children[body]);
+            i += 1;
+        }
+        order
+    }
+    /// The bodies that `body` orbits, nearest first.
+    fn ancestors(&self, body: Body) -> impl Iterator<Item = Body> + '_ {
+        std::iter::successors(self.parents[body], move |&body| self.parents[body])
+    }
+    /// What `body` orbits `steps` levels further in, or the root if it's fewer levels deep.
+    fn ancestor(&self, mut body: Body, steps: u32) -> Body {
+        for (k, jumps) in self.jumps.iter().enumerate() {
+            if steps >> k & 1 == 1 {
+                body = jumps[body];
             }
         }
-        path
+        body
+    }
+    /// The deepest body that both `a` and `b` are in or orbit, if they're in the same tree.
+    fn common_ancestor(&self, a: Body, b: Body) -> Option<Body> {
+        let (a, b) = match self.depths[a] >= self.depths[b] {
+            true => (a, b),
+            false => (b, a),
+        };
+        let (mut a, mut b) = (self.ancestor(a, self.depths[a] - self.depths[b]), b);
+        if a == b {
+            return Some(a);
+        }
+        for jumps in self.jumps.iter().rev() {
+            if jumps[a] != jumps[b] {
+                a = jumps[a];
+                b = jumps[b];
+            }
+        }
+        let (a, b) = (self.jumps[0][a], self.jumps[0][b]);
+        (a == b).then_some(a)
+    }
+    /// The body that everything orbits in the end, in a map that's been validated.
+    fn root(&self) -> Option<Body> {
+        (0..self.names.len()).find(|&body| self.parents[body].is_none())
+    }
+    /// Every body from `a` in to where it meets `b`'s chain and back out to `b`, if they're
+    /// in the same tree.
+    fn path(&self, a: Body, b: Body) -> Option<Vec<Body>> {
+        let common = self.common_ancestor(a, b)?;
+        let chain = |body| std::iter::once(body).chain(self.ancestors(body));
+        let mut path = chain(a).take_while(|&body| body != common).collect::<Vec<_>>();
+        path.push(common);
+        let back = chain(b).take_while(|&body| body != common).collect::<Vec<_>>();
+        path.extend(back.into_iter().rev());
+        Some(path)
+    }
+    /// How many orbits apart `a` and `b` are, if they're in the same tree.
+    fn distance(&self, a: Body, b: Body) -> Option<u32> {
+        let common = self.common_ancestor(a, b)?;
+        Some(self.depths[a] + self.depths[b] - 2 * self.depths[common])
+    }
+    fn subtree_size(&self, body: Body) -> usize {
+        self.sizes[body]
+    }
+    /// Checks that every body orbits exactly one other, except for a single root that
+    /// everything orbits in the end.
+    fn validate(&self) -> Result<(), Vec<Problem>> {
+        let name = |body: Body| self.names[body].clone();
+        let mut problems = Vec::new();
+        for (body, listings) in self.listings.iter().enumerate() {
+            if listings.iter().any(|&(_, center)| center != listings[0].1) {
+                problems.push(Problem::Centers {
+                    body: name(body),
+                    lines: listings.iter().map(|&(line, _)| line).collect(),
+                });
+            }
+        }
+        // Whatever `order` can't reach from a root is on a loop or orbits something that is.
+        // Walking up from those bodies finds each loop once.
+        let mut walked = vec![None; self.names.len()];
+        for body in self.order() {
+            walked[body] = Some(usize::MAX);
+        }
+        for start in 0..self.names.len() {
+            let mut body = start;
+            let mut path = Vec::new();
+            while walked[body].is_none() {
+                walked[body] = Some(start);
+                path.push(body);
+                // Everything without a parent is a root, and `order` reached all of those.
+                body = self.parents[body].expect("Unreached roots");
+            }
+            if walked[body] == Some(start) {
+                let cycle = &path[path.iter().position(|&b| b == body).unwrap_or(0)..];
+                let mut lines = cycle
+                    .iter()
+                    .filter_map(|&body| self.listings[body].last().map(|&(line, _)| line))
+                    .collect::<Vec<_>>();
+                lines.sort_unstable();
+                problems.push(Problem::Cycle {
+                    bodies: cycle.iter().map(|&body| name(body)).collect(),
+                    lines,
+                });
+            }
+        }
+        let roots = (0..self.names.len())
+            .filter(|&body| self.parents[body].is_none())
+            .map(|body| (name(body), self.first_lines[body]))
+            .collect::<Vec<_>>();
+        if roots.len() > 1 {
+            problems.push(Problem::Disconnected { roots });
+        }
+        match problems.is_empty() {
+            true => Ok(()),
+            false => Err(problems),
+        }
+    }
+    fn total_orbits(&self) -> u64 {
+        self.depths.iter().map(|&depth| u64::from(depth)).sum()
     }
 }
 
-impl FromIterator<Pair> for Planets {
-    fn from_iter<T: IntoIterator<Item = Pair>>(iter: T) -> Self {
-        let mut map = HashMap::new();
-        for pair in iter {
-            let satellite = pair.companion;
-            let center = pair.center;
-            map.insert(satellite, center);
+/// Builds the map from pairs and the lines they're on. Nothing is checked here; that's what
+/// `Planets::validate` is for.
+impl FromIterator<(usize, Pair)> for Planets {
+    fn from_iter<T: IntoIterator<Item = (usize, Pair)>>(iter: T) -> Self {
+        let mut planets = Self::default();
+        for (line, pair) in iter {
+            let center = planets.intern(pair.center, line);
+            let satellite = planets.intern(pair.companion, line);
+            planets.listings[satellite].push((line, center));
+            if let Some(old) = planets.parents[satellite].replace(center) {
+                planets.children[old].retain(|&body| body != satellite);
+            }
+            planets.children[center].push(satellite);
+        }
+        let n = planets.names.len();
+        let order = planets.order();
+        planets.depths = vec![0; n];
+        for &body in &order {
+            if let Some(center) = planets.parents[body] {
+                planets.depths[body] = planets.depths[center] + 1;
+            }
+        }
+        planets.sizes = vec![1; n];
+        for &body in order.iter().rev() {
+            if let Some(center) = planets.parents[body] {
+                planets.sizes[center] += planets.sizes[body];
+            }
+        }
+        let deepest = planets.depths.iter().copied().max().unwrap_or(0);
+        let levels = (u32::BITS - deepest.leading_zeros()).max(1);
+        let roots = (0..n).map(|body| planets.parents[body].unwrap_or(body));
+        planets.jumps = vec![roots.collect()];
+        for k in 1..levels as usize {
+            let half = &planets.jumps[k - 1];
+            let jumps = half.iter().map(|&body| half[body]).collect();
+            planets.jumps.push(jumps);
         }
-        Self { map }
+        planets
     }
 }
 
 struct Pair {
-    center: Planet,
-    companion: Planet,
+    center: String,
+    companion: String,
 }
 
 impl FromStr for Pair {
-    type Err = ();
+    type Err = &'static str;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut planets = s.split(')').filter_map(|x| x.parse::<Planet>().ok());
-        if let (Some(center), Some(companion)) = (planets.next(), planets.next()) {
-            Ok(Self { center, companion })
-        } else {
-            Err(())
+        let mut names = s.split(')');
+        match (names.next(), names.next(), names.next()) {
+            (Some(center), Some(companion), None) if !center.is_empty() && !companion.is_empty() => {
+                Ok(Self {
+                    center: center.to_string(),
+                    companion: companion.to_string(),
+                })
+            }
+            _ => Err("two names separated by ')'"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn deep() {
+        // A chain this long would overflow the stack if anything recursed along it.
+        let n = 200_000;
+        let planets = (1..n)
+            .map(|i| {
+                let pair = Pair {
+                    center: format!("P{}", i - 1),
+                    companion: format!("P{}", i),
+                };
+                (i as usize, pair)
+            })
+            .collect::<Planets>();
+        assert_eq!(planets.validate(), Ok(()));
+        assert_eq!(planets.total_orbits(), n * (n - 1) / 2);
+        let last = planets.id(&format!("P{}", n - 1)).unwrap();
+        assert_eq!(planets.ancestors(last).count() as u64, n - 1);
+        let first = planets.id("P0").unwrap();
+        assert_eq!(planets.distance(first, last), Some(n as u32 - 1));
+        assert_eq!(planets.subtree_size(first) as u64, n);
+    }
+    #[test]
+    fn queries() {
+        let input = "COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L\nX)Y";
+        let planets = parse(input).unwrap();
+        let id = |name| planets.id(name).unwrap();
+        let names = |bodies: Vec<Body>| {
+            bodies
+                .into_iter()
+                .map(|body| planets.names[body].as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(planets.ancestors(id("L")).collect()),
+            ["K", "J", "E", "D", "C", "B", "COM"]
+        );
+        assert_eq!(planets.common_ancestor(id("L"), id("I")), Some(id("D")));
+        assert_eq!(planets.common_ancestor(id("H"), id("B")), Some(id("B")));
+        assert_eq!(planets.common_ancestor(id("H"), id("Y")), None);
+        assert_eq!(planets.distance(id("L"), id("H")), Some(8));
+        assert_eq!(planets.subtree_size(id("D")), 7);
+        assert_eq!(transfers(&planets, "L", "I").unwrap(), Answer::Integer(3));
+        assert!(transfers(&planets, "COM", "L").is_err());
+        assert!(transfers(&planets, "L", "Y").is_err());
+    }
+    #[test]
+    fn validation() {
+        let problems = |input| parse(input).unwrap().validate().unwrap_err();
+        assert_eq!(
+            problems("COM)B\nB)C\nCOM)C\nB)C"),
+            [Problem::Centers {
+                body: "C".to_string(),
+                lines: vec![2, 3, 4],
+            }]
+        );
+        assert_eq!(
+            problems("COM)B\nB)C\nD)E\nE)F\nF)D\nE)G"),
+            [Problem::Cycle {
+                bodies: vec!["D".to_string(), "F".to_string(), "E".to_string()],
+                lines: vec![3, 4, 5],
+            }]
+        );
+        let problems = problems("COM)B\nB)C\n\nX)Y");
+        assert_eq!(
+            problems,
+            [Problem::Disconnected {
+                roots: vec![("COM".to_string(), 1), ("X".to_string(), 4)],
+            }]
+        );
+        assert_eq!(
+            invalid(problems).to_string(),
+            "Invalid orbit map: the map splits into 2 systems, around COM (line 1) and X (line 4)"
+        );
+        assert_eq!(
+            transfers(&parse("COM)B\nB)YOU").unwrap(), "YOU", "SAN")
+                .unwrap_err()
+                .to_string(),
+            "There's no SAN in the map"
+        );
+    }
+}
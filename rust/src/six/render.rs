@@ -0,0 +1,167 @@
+use super::{Body, Planets};
+use std::{collections::HashSet, fmt::Write};
+
+/// Which part of the map to draw.
+pub(super) struct View {
+    pub(super) root: Body,
+    /// How many levels below `root` to go, if not all the way.
+    pub(super) depth: Option<u32>,
+    /// The bodies to pick out, such as the path between YOU and SAN.
+    pub(super) path: Vec<Body>,
+}
+
+impl View {
+    /// Calls `f` with each body to draw, its level below the root and whether it's the last
+    /// of its siblings, parents first and siblings in name order. Bodies past the depth limit
+    /// are left out.
+    fn walk(&self, planets: &Planets, mut f: impl FnMut(Body, u32, bool)) {
+        let mut stack = vec![(self.root, 0, true)];
+        while let Some((body, level, last)) = stack.pop() {
+            f(body, level, last);
+            if self.depth.is_some_and(|depth| level >= depth) {
+                continue;
+            }
+            let children = self.children(planets, body);
+            // Pushed in reverse so that they come off the stack in order.
+            for (i, &child) in children.iter().enumerate().rev() {
+                stack.push((child, level + 1, i + 1 == children.len()));
+            }
+        }
+    }
+    fn children(&self, planets: &Planets, body: Body) -> Vec<Body> {
+        let mut children = planets.children[body].clone();
+        children.sort_by(|&a, &b| planets.names[a].cmp(&planets.names[b]));
+        children
+    }
+    /// How many bodies under `body` were cut off by the depth limit.
+    fn hidden(&self, planets: &Planets, body: Body, level: u32) -> usize {
+        match self.depth {
+            Some(depth) if level >= depth => planets.subtree_size(body) - 1,
+            _ => 0,
+        }
+    }
+}
+
+fn escape(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", escape(name))
+}
+
+/// Draws the map as a Graphviz graph, with each center pointing at what orbits it.
+pub(super) fn dot(planets: &Planets, view: &View) -> String {
+    let path = view.path.iter().copied().collect::<HashSet<_>>();
+    let mut dot = String::from("digraph orbits {\n    rankdir=LR;\n    node [shape=ellipse];\n");
+    view.walk(planets, |body, level, _| {
+        let name = &planets.names[body];
+        let mut attributes = Vec::new();
+        match view.hidden(planets, body, level) {
+            0 => {}
+            hidden => attributes.push(format!("label=\"{}\\n+{}\"", escape(name), hidden)),
+        }
+        if path.contains(&body) {
+            attributes.push("color=red, fontcolor=red".to_string());
+        }
+        // Writing to a `String` can't fail.
+        let _ = match attributes.is_empty() {
+            true => writeln!(dot, "    {};", quote(name)),
+            false => writeln!(dot, "    {} [{}];", quote(name), attributes.join(", ")),
+        };
+        if let Some(center) = planets.parents[body].filter(|_| body != view.root) {
+            let on_path = path.contains(&body) && path.contains(&center);
+            let style = if on_path { " [color=red, penwidth=2]" } else { "" };
+            let _ = writeln!(
+                dot,
+                "    {} -> {}{};",
+                quote(&planets.names[center]),
+                quote(name),
+                style
+            );
+        }
+    });
+    dot.push_str("}\n");
+    dot
+}
+
+/// Draws the map as an indented tree, like `tree(1)`. Bodies on the path are starred, and
+/// bodies with more under them than the depth limit shows say how many more.
+pub(super) fn tree(planets: &Planets, view: &View) -> String {
+    let path = view.path.iter().copied().collect::<HashSet<_>>();
+    let mut tree = String::new();
+    // Whether each level above the current body still has siblings to come.
+    let mut open = Vec::<bool>::new();
+    view.walk(planets, |body, level, last| {
+        open.truncate(level as usize);
+        if level > 0 {
+            for &more in &open[1..] {
+                tree.push_str(if more { "│   " } else { "    " });
+            }
+            tree.push_str(if last { "└── " } else { "├── " });
+        }
+        open.push(!last);
+        tree.push_str(&planets.names[body]);
+        if path.contains(&body) {
+            tree.push_str(" *");
+        }
+        match view.hidden(planets, body, level) {
+            0 => {}
+            hidden => {
+                let _ = write!(tree, " (+{})", hidden);
+            }
+        }
+        tree.push('\n');
+    });
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse;
+    use super::*;
+    const MAP: &str = "COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L\nK)YOU\nI)SAN";
+    #[test]
+    fn trees() {
+        let planets = parse(MAP).unwrap();
+        let id = |name| planets.id(name).unwrap();
+        let view = View {
+            root: id("D"),
+            depth: Some(2),
+            path: planets.path(id("YOU"), id("SAN")).unwrap(),
+        };
+        assert_eq!(
+            tree(&planets, &view),
+            "\
+D *
+├── E *
+│   ├── F
+│   └── J * (+3)
+└── I *
+    └── SAN *
+"
+        );
+        let view = View {
+            root: id("COM"),
+            depth: None,
+            path: Vec::new(),
+        };
+        assert_eq!(tree(&planets, &view).lines().count(), 14);
+    }
+    #[test]
+    fn graphs() {
+        let planets = parse(MAP).unwrap();
+        let id = |name| planets.id(name).unwrap();
+        let view = View {
+            root: id("COM"),
+            depth: Some(4),
+            path: planets.path(id("YOU"), id("SAN")).unwrap(),
+        };
+        let dot = dot(&planets, &view);
+        assert!(dot.contains("    \"D\" -> \"I\" [color=red, penwidth=2];\n"));
+        assert!(dot.contains("    \"B\" -> \"G\";\n"));
+        assert!(dot.contains("    \"E\" [label=\"E\\n+5\", color=red, fontcolor=red];\n"));
+        assert!(!dot.contains("\"F\""));
+        assert!(!dot.contains("-> \"COM\""));
+    }
+}
@@ -1,170 +1,541 @@
+use crate::{
+    answer::Answer,
+    error::{self, Error, ParseError},
+    grid::{self, Direction, Point, Vector},
+    params::Params,
+    Part,
+};
 use std::{
-    collections::HashSet,
+    any::type_name,
+    collections::{BTreeMap, HashMap},
     convert::TryFrom,
-    ops::{Add, Div, Mul},
-    str::FromStr,
+    fmt::{Debug, Display},
+    hash::Hash,
 };
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-struct Point {
-    x: i16,
-    y: i16,
+mod render;
+
+/// An integer type that wire coordinates can be stored in. Arithmetic is done in `i128` and
+/// narrowed back, so that running off the end of the type is caught rather than wrapping.
+trait Coordinate: grid::Coordinate + Debug + Display + Hash + Into<i128> + TryFrom<i128> {}
+
+impl Coordinate for i16 {}
+impl Coordinate for i32 {}
+impl Coordinate for i64 {}
+
+fn narrow<T: Coordinate>(value: i128) -> Option<T> {
+    T::try_from(value).ok()
+}
+
+/// Manhattan length of a vector, worked out wide enough that it can't overflow.
+fn length<T: Coordinate>(v: Vector<T>) -> u128 {
+    v.x.into().unsigned_abs() + v.y.into().unsigned_abs()
+}
+
+/// Manhattan distance from the origin.
+fn distance<T: Coordinate>(point: Point<T>) -> u128 {
+    length(point - Point::origin())
+}
+
+/// Moves `point` by `delta`, unless that would leave the range of `T`.
+fn checked_add<T: Coordinate>(point: Point<T>, delta: Vector<T>) -> Option<Point<T>> {
+    Some(Point::new(
+        narrow(point.x.into() + delta.x.into())?,
+        narrow(point.y.into() + delta.y.into())?,
+    ))
 }
 
-impl Point {
-    fn new(x: i16, y: i16) -> Self {
-        Self { x, y }
+const STEP: &str = "a direction (U, D, L or R) followed by a distance";
+
+fn parse_step<T: Coordinate>(s: &str) -> Result<Vector<T>, String> {
+    let direction = s
+        .chars()
+        .next()
+        .and_then(|c| Direction::try_from(c).ok())
+        .ok_or_else(|| STEP.to_string())?;
+    let amount = s[1..].parse::<i128>().map_err(|_| STEP.to_string())?;
+    let unit = direction.unit::<i8>();
+    let (x, y) = (i128::from(unit.x) * amount, i128::from(unit.y) * amount);
+    match (narrow(x), narrow(y)) {
+        (Some(x), Some(y)) => Ok(Vector::new(x, y)),
+        _ => Err(format!("a distance that fits in {}", type_name::<T>())),
     }
-    fn distance(self) -> u16 {
-        (self.x.abs() + self.y.abs()) as u16
+}
+
+/// One straight run of a wire, along with how far the wire had come before it started.
+#[derive(Clone, Copy, Debug)]
+struct Segment<T> {
+    start: Point<T>,
+    end: Point<T>,
+    steps: u64,
+}
+
+impl<T: Coordinate> Segment<T> {
+    fn is_horizontal(&self) -> bool {
+        self.start.y == self.end.y
     }
-    fn decompose(self) -> Vec<Point> {
-        assert!(self.x == 0 || self.y == 0);
-        let magnitude = self.distance() as i16;
-        vec![self / magnitude; magnitude as usize]
+    /// The fixed coordinate, and the range covered by the other one.
+    fn span(&self) -> (T, T, T) {
+        if self.is_horizontal() {
+            let (a, b) = (self.start.x, self.end.x);
+            (self.start.y, a.min(b), a.max(b))
+        } else {
+            let (a, b) = (self.start.y, self.end.y);
+            (self.start.x, a.min(b), a.max(b))
+        }
     }
-    fn unit(direction: Direction) -> Self {
-        match direction {
-            Direction::Up => Self::new(0, 1),
-            Direction::Down => Self::new(0, -1),
-            Direction::Left => Self::new(-1, 0),
-            Direction::Right => Self::new(1, 0),
+    fn contains(&self, point: Point<T>) -> bool {
+        let (fixed, lo, hi) = self.span();
+        if self.is_horizontal() {
+            point.y == fixed && (lo..=hi).contains(&point.x)
+        } else {
+            point.x == fixed && (lo..=hi).contains(&point.y)
         }
     }
+    /// Steps taken along the wire to reach `point`, which must lie on this segment. This can't
+    /// overflow, as `Wires::new` checks that the whole wire's length fits.
+    fn steps_to(&self, point: Point<T>) -> u64 {
+        let dx = point.x.into() - self.start.x.into();
+        let dy = point.y.into() - self.start.y.into();
+        self.steps + (dx.unsigned_abs() + dy.unsigned_abs()) as u64
+    }
 }
 
-impl std::fmt::Display for Point {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
-    }
+fn overflow(message: String) -> Error {
+    Error::Overflow(message)
 }
 
-impl Add<Self> for Point {
-    type Output = Self;
-    fn add(self, other: Self) -> Self::Output {
-        Self {
-            x: self.x + other.x,
-            y: self.y + other.y,
+/// Lays a wire's steps end to end, starting from the origin. Zero-length steps are dropped.
+fn segments<T: Coordinate>(wire: usize, path: &[Vector<T>]) -> Result<Vec<Segment<T>>, Error> {
+    let mut segments = Vec::with_capacity(path.len());
+    let (mut pos, mut steps) = (Point::origin(), 0u64);
+    for (i, &delta) in path.iter().enumerate() {
+        if length(delta) == 0 {
+            continue;
         }
+        let end = checked_add(pos, delta).ok_or_else(|| {
+            overflow(format!(
+                "wire {} leaves the range of {} at step {}",
+                wire + 1,
+                type_name::<T>(),
+                i + 1
+            ))
+        })?;
+        segments.push(Segment {
+            start: pos,
+            end,
+            steps,
+        });
+        pos = end;
+        steps = u64::try_from(length(delta))
+            .ok()
+            .and_then(|length| steps.checked_add(length))
+            .ok_or_else(|| overflow(format!("wire {} is too long to count", wire + 1)))?;
     }
+    Ok(segments)
 }
 
-impl Mul<i16> for Point {
-    type Output = Self;
-    fn mul(self, c: i16) -> Self::Output {
-        Self {
-            x: c * self.x,
-            y: c * self.y,
+/// Finds where horizontal segments cross vertical ones by sweeping a line across the x axis,
+/// keeping the horizontal segments it currently passes through ordered by y.
+fn perpendicular<T: Coordinate>(
+    horizontal: &[Segment<T>],
+    vertical: &[Segment<T>],
+) -> Vec<(Point<T>, u64, u64)> {
+    // At a given x, segments start before verticals are checked, which is before segments end,
+    // so that crossings at the ends of segments count.
+    let mut events = Vec::with_capacity(2 * horizontal.len() + vertical.len());
+    for (i, segment) in horizontal.iter().enumerate() {
+        let (_, lo, hi) = segment.span();
+        events.push((lo, 0, i));
+        events.push((hi, 2, i));
+    }
+    for (i, segment) in vertical.iter().enumerate() {
+        events.push((segment.start.x, 1, i));
+    }
+    events.sort_unstable();
+    let mut active = BTreeMap::<T, Vec<usize>>::new();
+    let mut crossings = Vec::new();
+    for (x, kind, i) in events {
+        match kind {
+            0 => active.entry(horizontal[i].start.y).or_default().push(i),
+            1 => {
+                let (_, lo, hi) = vertical[i].span();
+                for (&y, segments) in active.range(lo..=hi) {
+                    let point = Point::new(x, y);
+                    for &h in segments {
+                        let steps = (horizontal[h].steps_to(point), vertical[i].steps_to(point));
+                        crossings.push((point, steps.0, steps.1));
+                    }
+                }
+            }
+            _ => {
+                let y = horizontal[i].start.y;
+                let segments = active.get_mut(&y).unwrap();
+                segments.retain(|&h| h != i);
+                if segments.is_empty() {
+                    active.remove(&y);
+                }
+            }
         }
     }
+    crossings
 }
 
-impl Div<i16> for Point {
-    type Output = Self;
-    fn div(self, c: i16) -> Self::Output {
-        Self {
-            x: self.x / c,
-            y: self.y / c,
+/// Finds where segments running along the same line overlap. Rather than every point of the
+/// shared stretch, only its ends and its point closest to the origin are reported; the step
+/// counts change linearly along it, so those are the only places either answer can be.
+///
+/// Where the segments of the `others` wires meet the stretch, or start or stop along it, are
+/// reported too, since an answer that those wires have to pass through can be there instead.
+fn collinear<T: Coordinate>(
+    one: &[Segment<T>],
+    two: &[Segment<T>],
+    others: &[Vec<Segment<T>>],
+) -> Vec<(Point<T>, u64, u64)> {
+    let mut lines = HashMap::<(bool, T), Vec<&Segment<T>>>::new();
+    for segment in two {
+        lines
+            .entry((segment.is_horizontal(), segment.span().0))
+            .or_default()
+            .push(segment);
+    }
+    let mut crossings = Vec::new();
+    for a in one {
+        let (fixed, lo, hi) = a.span();
+        for b in lines.get(&(a.is_horizontal(), fixed)).into_iter().flatten() {
+            let (_, other_lo, other_hi) = b.span();
+            let (lo, hi) = (lo.max(other_lo), hi.min(other_hi));
+            if lo > hi {
+                continue;
+            }
+            let clamp = |c: i128| narrow::<T>(c.clamp(lo.into(), hi.into())).unwrap();
+            let mut candidates = vec![lo, hi, clamp(0)];
+            // The origin itself doesn't count, so try either side of it too.
+            if fixed == T::from(0) {
+                candidates.push(clamp(-1));
+                candidates.push(clamp(1));
+            }
+            for other in others.iter().flatten() {
+                let (other_fixed, other_lo, other_hi) = other.span();
+                if other.is_horizontal() == a.is_horizontal() {
+                    if other_fixed == fixed {
+                        candidates.push(clamp(other_lo.into()));
+                        candidates.push(clamp(other_hi.into()));
+                    }
+                } else if (other_lo..=other_hi).contains(&fixed)
+                    && (lo..=hi).contains(&other_fixed)
+                {
+                    candidates.push(other_fixed);
+                }
+            }
+            for c in candidates {
+                let point = if a.is_horizontal() {
+                    Point::new(c, fixed)
+                } else {
+                    Point::new(fixed, c)
+                };
+                crossings.push((point, a.steps_to(point), b.steps_to(point)));
+            }
         }
     }
+    crossings
+}
+
+/// A point (other than the origin) where some of the wires meet.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Crossing<T> {
+    point: Point<T>,
+    /// Indices of the wires that meet here, in input order.
+    wires: Vec<usize>,
+    /// The fewest steps each of those wires takes to get here, combined.
+    steps: u64,
 }
 
-impl FromStr for Point {
-    type Err = ();
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let direction = s.parse::<Direction>()?;
-        let amount = s[1..].parse::<i16>().map_err(|_| ())?;
-        Ok(Point::unit(direction) * amount)
+impl<T: Display> Display for Crossing<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let wires = self
+            .wires
+            .iter()
+            .map(|i| (i + 1).to_string())
+            .collect::<Vec<_>>();
+        // Wires are numbered from 1, as in the other messages about them.
+        write!(f, "{} (wires {})", self.point, wires.join(" and "))
     }
 }
 
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+struct Wires<T> {
+    wires: Vec<Vec<Segment<T>>>,
 }
 
-impl TryFrom<char> for Direction {
-    type Error = ();
-    fn try_from(c: char) -> Result<Self, Self::Error> {
-        match c {
-            'U' => Ok(Self::Up),
-            'D' => Ok(Self::Down),
-            'R' => Ok(Self::Right),
-            'L' => Ok(Self::Left),
-            _ => Err(()),
+impl<T: Coordinate> Wires<T> {
+    /// Lays out the wires, checking that every step count and distance the solver might need
+    /// fits in a `u64`.
+    fn new(paths: &[Vec<Vector<T>>]) -> Result<Self, Error> {
+        let wires = paths
+            .iter()
+            .enumerate()
+            .map(|(i, path)| segments(i, path))
+            .collect::<Result<Vec<_>, _>>()?;
+        // Crossings combine the steps of several wires, so those have to fit together too.
+        wires
+            .iter()
+            .map(|wire| wire.last().map_or(0, |last| last.steps_to(last.end)))
+            .try_fold(0u64, |total, length| total.checked_add(length))
+            .ok_or_else(|| overflow("the wires are too long to count together".to_string()))?;
+        Ok(Self { wires })
+    }
+    /// Where wires `i` and `j` cross, with the fewest steps each takes to get there. Where
+    /// they run together, the places that the `others` wires cross that stretch are included.
+    fn pair(
+        &self,
+        i: usize,
+        j: usize,
+        others: &[Vec<Segment<T>>],
+    ) -> HashMap<Point<T>, (u64, u64)> {
+        let (one, two) = (&self.wires[i], &self.wires[j]);
+        let split = |wire: &[Segment<T>]| -> (Vec<Segment<T>>, Vec<Segment<T>>) {
+            wire.iter().partition(|segment| segment.is_horizontal())
+        };
+        let ((h1, v1), (h2, v2)) = (split(one), split(two));
+        let mut pairs = perpendicular(&h1, &v2);
+        pairs.extend(perpendicular(&h2, &v1).into_iter().map(|(p, b, a)| (p, a, b)));
+        pairs.extend(collinear(one, two, others));
+        let mut fewest = HashMap::<Point<T>, (u64, u64)>::new();
+        for (point, a, b) in pairs {
+            if point == Point::origin() {
+                continue;
+            }
+            let entry = fewest.entry(point).or_insert((a, b));
+            *entry = (entry.0.min(a), entry.1.min(b));
         }
+        fewest
     }
-}
-
-impl FromStr for Direction {
-    type Err = ();
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        s.chars().next().ok_or(()).and_then(Direction::try_from)
+    /// Every crossing between two wires, for each pair of wires.
+    fn pairwise(&self) -> Vec<Crossing<T>> {
+        let mut crossings = Vec::new();
+        for i in 0..self.wires.len() {
+            for j in (i + 1)..self.wires.len() {
+                crossings.extend(self.pair(i, j, &[]).into_iter().map(|(point, (a, b))| Crossing {
+                    point,
+                    wires: vec![i, j],
+                    steps: a + b,
+                }));
+            }
+        }
+        crossings
+    }
+    /// The crossings that every wire passes through.
+    fn common(&self) -> Vec<Crossing<T>> {
+        if self.wires.len() < 2 {
+            return Vec::new();
+        }
+        self.pair(0, 1, &self.wires[2..])
+            .into_iter()
+            .filter_map(|(point, (a, b))| {
+                // Any further wires have to pass through the point as well.
+                self.wires[2..]
+                    .iter()
+                    .map(|wire| {
+                        wire.iter()
+                            .filter(|segment| segment.contains(point))
+                            .map(|segment| segment.steps_to(point))
+                            .min()
+                    })
+                    .sum::<Option<u64>>()
+                    .map(|rest| Crossing {
+                        point,
+                        wires: (0..self.wires.len()).collect(),
+                        steps: a + b + rest,
+                    })
+            })
+            .collect()
     }
 }
 
-fn part1(paths: &[Vec<Point>]) -> String {
-    let closest = paths
+/// The crossing nearest the origin by Manhattan distance.
+fn closest<T: Coordinate>(crossings: &[Crossing<T>]) -> Option<&Crossing<T>> {
+    crossings
         .iter()
-        .map(|path| {
-            path.iter()
-                .flat_map(|pt| pt.decompose())
-                .scan(Point::new(0, 0), |pos, delta| {
-                    let new = *pos + delta;
-                    *pos = new;
-                    Some(new)
-                })
-                .collect::<HashSet<_>>()
-        })
-        .fold(HashSet::new(), |common, set| {
-            if common.is_empty() {
-                set
-            } else {
-                common.intersection(&set).copied().collect()
-            }
-        })
+        .min_by_key(|c| (distance(c.point), c.wires.clone(), c.point.x, c.point.y))
+}
+
+/// The crossing the wires reach in the fewest combined steps.
+fn fewest<T: Coordinate>(crossings: &[Crossing<T>]) -> Option<&Crossing<T>> {
+    crossings
         .iter()
-        .map(|pt| pt.distance())
-        .min();
-    format!("{}", closest.unwrap())
-}
-
-fn part2(paths: &[Vec<Point>]) -> String {
-    let mut paths = paths.iter().map(|path| {
-        path.iter()
-            .flat_map(|pt| pt.decompose())
-            .scan(Point::new(0, 0), |pos, delta| {
-                let new = *pos + delta;
-                *pos = new;
-                Some(new)
-            })
-            .enumerate()
-            .collect::<Vec<_>>()
-    });
-    let one = paths.next().unwrap();
-    let two = paths.next().unwrap();
-    for (i, p) in one {
-        for (j, q) in two.iter() {
-            if p == *q {
-                return format!("{}", i + j + 2);
-            }
+        .min_by_key(|c| (c.steps, c.wires.clone(), c.point.x, c.point.y))
+}
+
+fn uncrossed(pairwise: bool) -> Answer {
+    Answer::None(match pairwise {
+        true => "no two wires cross".to_string(),
+        false => "the wires don't all cross anywhere".to_string(),
+    })
+}
+
+fn part1<T: Coordinate>(crossings: &[Crossing<T>], pairwise: bool) -> Result<Answer, Error> {
+    match closest(crossings) {
+        Some(closest) if pairwise => {
+            Ok(Answer::integer(distance(closest.point))?.with_detail(format!("at {}", closest)))
+        }
+        Some(closest) => Answer::integer(distance(closest.point)),
+        None => Ok(uncrossed(pairwise)),
+    }
+}
+
+fn part2<T: Coordinate>(crossings: &[Crossing<T>], pairwise: bool) -> Result<Answer, Error> {
+    match fewest(crossings) {
+        Some(fewest) if pairwise => {
+            Ok(Answer::integer(fewest.steps)?.with_detail(format!("at {}", fewest)))
         }
+        Some(fewest) => Answer::integer(fewest.steps),
+        None => Ok(uncrossed(pairwise)),
     }
-    String::new()
 }
 
-pub(crate) fn run(input: String) -> [String; 2] {
-    let points = input
-        .lines()
-        .map(|line| {
-            line.split(',')
-                .filter_map(|x| x.parse::<Point>().ok())
-                .collect::<Vec<_>>()
+/// By default, or with `crossings=all`, only crossings that every wire passes through count.
+/// With `crossings=pairs`, any two wires crossing will do, and the answers say which wires they
+/// were. Coordinates are `i32`s unless `coordinates` says to use `i16` or `i64` instead.
+pub(crate) fn run(input: &str, part: Part, params: &Params) -> Result<Answer, Error> {
+    match params.choice("coordinates", &["i16", "i32", "i64"])? {
+        Some("i16") => solve::<i16>(input, part, params),
+        Some("i64") => solve::<i64>(input, part, params),
+        _ => solve::<i32>(input, part, params),
+    }
+}
+
+fn parse<T: Coordinate>(input: &str) -> Result<Wires<T>, Error> {
+    let points = error::lines(input)
+        .map(|(line, text)| {
+            error::fields(text, ',')
+                .map(|(column, step)| {
+                    parse_step::<T>(step)
+                        .map_err(|expected| ParseError::new(line, column, step, &expected))
+                })
+                .collect::<Result<Vec<_>, _>>()
         })
-        .collect::<Vec<Vec<_>>>();
-    let closest = part1(&points);
-    let fewest = part2(&points);
-    [closest, fewest]
+        .collect::<Result<Vec<Vec<_>>, _>>()?;
+    Wires::new(&points)
+}
+
+fn crossings<T: Coordinate>(
+    wires: &Wires<T>,
+    params: &Params,
+) -> Result<(Vec<Crossing<T>>, bool), Error> {
+    Ok(match params.choice("crossings", &["all", "pairs"])? {
+        Some("pairs") => (wires.pairwise(), true),
+        _ => (wires.common(), false),
+    })
+}
+
+fn solve<T: Coordinate>(input: &str, part: Part, params: &Params) -> Result<Answer, Error> {
+    let wires = parse::<T>(input)?;
+    let (crossings, pairwise) = crossings(&wires, params)?;
+    match part {
+        Part::One => part1(&crossings, pairwise),
+        Part::Two => part2(&crossings, pairwise),
+    }
+}
+
+/// Draws the wires as an `svg` picture, or as `ascii` art like the puzzle's for small inputs.
+/// Takes the same parameters as `run`.
+pub(crate) fn draw(input: &str, format: &str, params: &Params) -> Result<Vec<u8>, Error> {
+    match params.choice("coordinates", &["i16", "i32", "i64"])? {
+        Some("i16") => draw_as::<i16>(input, format, params),
+        Some("i64") => draw_as::<i64>(input, format, params),
+        _ => draw_as::<i32>(input, format, params),
+    }
+}
+
+fn draw_as<T: Coordinate>(input: &str, format: &str, params: &Params) -> Result<Vec<u8>, Error> {
+    let wires = parse::<T>(input)?;
+    let drawing = match format {
+        "svg" => render::svg(&wires, &crossings(&wires, params)?.0),
+        "ascii" => render::ascii(&wires)?,
+        _ => {
+            return Err(Error::Unsupported(format!(
+                "Day 3 can't be drawn as {} (try svg or ascii)",
+                format
+            )))
+        }
+    };
+    Ok(drawing.into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn wires(input: &str) -> Wires<i32> {
+        let paths = input
+            .lines()
+            .map(|line| line.split(',').map(|step| parse_step(step).unwrap()).collect())
+            .collect::<Vec<Vec<_>>>();
+        Wires::new(&paths).unwrap()
+    }
+    #[test]
+    fn overlapping() {
+        // The wires share the stretch from (2, 0) to (4, 0), heading the same way.
+        let wires = wires("R4,U1\nU1,R2,D1,R5");
+        let crossings = wires.pair(0, 1, &[]);
+        assert_eq!(crossings.get(&Point::new(2, 0)), Some(&(2, 4)));
+        assert_eq!(crossings.get(&Point::new(4, 0)), Some(&(4, 6)));
+        let common = wires.common();
+        assert_eq!(closest(&common).unwrap().point, Point::new(2, 0));
+        assert_eq!(fewest(&common).unwrap().steps, 6);
+    }
+    #[test]
+    fn three_wires() {
+        let wires = wires("R8,U5,L5,D3\nU7,R6,D4,L4\nR3,U3");
+        let crossing = |x, y, wires, steps| Crossing {
+            point: Point::new(x, y),
+            wires,
+            steps,
+        };
+        assert_eq!(wires.common(), vec![crossing(3, 3, vec![0, 1, 2], 20 + 20 + 6)]);
+        // The third wire crosses the middle of the stretch that the first two share.
+        let shared = self::wires("R10\nR10\nU1,R5,D2");
+        assert_eq!(shared.common(), vec![crossing(5, 0, vec![0, 1, 2], 5 + 5 + 7)]);
+        // Where it runs along them too, the answers are at the ends of all three.
+        let shared = self::wires("R10\nR10\nU1,R3,D1,R4");
+        let common = shared.common();
+        assert_eq!(closest(&common).unwrap().point, Point::new(3, 0));
+        assert_eq!(fewest(&common).unwrap().steps, 3 + 3 + 5);
+        let pairwise = wires.pairwise();
+        // The third wire runs along the first for a while, which adds a few more.
+        assert_eq!(pairwise.len(), 2 + 4 + 1);
+        assert_eq!(closest(&pairwise), Some(&crossing(1, 0, vec![0, 2], 2)));
+        assert_eq!(fewest(&pairwise), Some(&crossing(1, 0, vec![0, 2], 2)));
+        assert_eq!(
+            part2(&pairwise, true).unwrap().described(),
+            "2 at (1, 0) (wires 1 and 3)"
+        );
+    }
+    #[test]
+    fn overflow() {
+        let params = "coordinates=i16".parse::<Params>().unwrap();
+        let err = run("R20000,R20000\nU1", Part::One, &params).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Overflow: wire 1 leaves the range of i16 at step 2"
+        );
+        let err = run("R40000\nU1", Part::One, &params).unwrap_err();
+        assert!(err.to_string().contains("a distance that fits in i16"));
+        let params = "coordinates=i8".parse::<Params>().unwrap();
+        assert_eq!(
+            run("R1\nU1", Part::One, &params).unwrap_err().to_string(),
+            "Invalid coordinates \"i8\" (try i16, i32 or i64)"
+        );
+        let params = "coordinates=i64".parse::<Params>().unwrap();
+        let wide = "R9000000000000000000,U1,L9000000000000000000\nU2,R9";
+        assert_eq!(run(wide, Part::One, &params).unwrap(), Answer::Integer(1));
+        assert_eq!(
+            run(wide, Part::Two, &params).unwrap(),
+            Answer::Integer(18000000000000000002)
+        );
+        let long = "R9000000000000000000,U1,L9000000000000000000\nU2,R9000000000000000000";
+        assert_eq!(
+            run(long, Part::Two, &params).unwrap_err().to_string(),
+            "Overflow: the wires are too long to count together"
+        );
+    }
 }
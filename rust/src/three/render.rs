@@ -0,0 +1,210 @@
+use super::{closest, distance, fewest, Coordinate, Crossing, Wires};
+use crate::{
+    error::Error,
+    grid::{Point, SparseGrid, Vector},
+};
+use std::fmt::Write;
+
+const COLORS: [&str; 8] = [
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#17becf",
+];
+
+/// The largest grid `ascii` will draw, in cells.
+const ASCII_LIMIT: i128 = 100 * 100;
+
+fn color(wire: usize) -> String {
+    match COLORS.get(wire) {
+        Some(color) => color.to_string(),
+        // Golden-angle hues stay apart from each other however many wires there are.
+        None => format!("hsl({}, 65%, 45%)", (wire * 137) % 360),
+    }
+}
+
+/// Widens a point so that drawing can do arithmetic on it without worrying about overflow.
+fn wide<T: Coordinate>(point: Point<T>) -> Point<i128> {
+    Point::new(point.x.into(), point.y.into())
+}
+
+/// The smallest and largest x and y over every point of every wire, including the origin.
+fn bounds<T: Coordinate>(wires: &Wires<T>) -> ((i128, i128), (i128, i128)) {
+    wires
+        .wires
+        .iter()
+        .flatten()
+        .map(|segment| wide(segment.end))
+        .fold(((0, 0), (0, 0)), |((x0, y0), (x1, y1)), p| {
+            ((x0.min(p.x), y0.min(p.y)), (x1.max(p.x), y1.max(p.y)))
+        })
+}
+
+/// Draws each wire in its own color, with the origin, every crossing and the two answers
+/// marked. The y axis points up, as in the puzzle.
+pub(super) fn svg<T: Coordinate>(wires: &Wires<T>, crossings: &[Crossing<T>]) -> String {
+    let ((x0, y0), (x1, y1)) = bounds(wires);
+    let extent = ((x1 - x0).max(y1 - y0) as f64).max(1.0);
+    let pad = extent * 0.05;
+    let (width, height) = ((x1 - x0) as f64 + 2.0 * pad, (y1 - y0) as f64 + 2.0 * pad);
+    let r = extent * 0.004;
+    let at = |point: Point<T>| {
+        let point = wide(point);
+        (point.x as f64, -point.y as f64)
+    };
+    let mut svg = String::new();
+    // Writing to a `String` can't fail.
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="800" height="{}">"#,
+        x0 as f64 - pad,
+        -y1 as f64 - pad,
+        width,
+        height,
+        (800.0 * height / width).round()
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="white"/>"#,
+        x0 as f64 - pad,
+        -y1 as f64 - pad,
+        width,
+        height
+    );
+    for (i, wire) in wires.wires.iter().enumerate() {
+        let points = std::iter::once(Point::origin())
+            .chain(wire.iter().map(|segment| segment.end))
+            .map(|point| {
+                let (x, y) = at(point);
+                format!("{},{}", x, y)
+            })
+            .collect::<Vec<_>>();
+        let _ = writeln!(
+            svg,
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="2" vector-effect="non-scaling-stroke"><title>wire {}</title></polyline>"#,
+            points.join(" "),
+            color(i),
+            i + 1
+        );
+    }
+    for crossing in crossings {
+        let (x, y) = at(crossing.point);
+        let _ = writeln!(
+            svg,
+            r#"<circle cx="{}" cy="{}" r="{}" fill="none" stroke="black" vector-effect="non-scaling-stroke"><title>{}, {} steps</title></circle>"#,
+            x, y, r, crossing, crossing.steps
+        );
+    }
+    let _ = writeln!(
+        svg,
+        r#"<circle cx="0" cy="0" r="{}" fill="black"><title>origin</title></circle>"#,
+        r * 1.5
+    );
+    if let Some(closest) = closest(crossings) {
+        let (x, y) = at(closest.point);
+        let _ = writeln!(
+            svg,
+            r#"<circle cx="{}" cy="{}" r="{}" fill="none" stroke="black" stroke-width="3" vector-effect="non-scaling-stroke"><title>closest: {}, distance {}</title></circle>"#,
+            x,
+            y,
+            r * 4.0,
+            closest,
+            distance(closest.point)
+        );
+    }
+    if let Some(fewest) = fewest(crossings) {
+        let (x, y) = at(fewest.point);
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="black" stroke-width="3" stroke-dasharray="4 2" vector-effect="non-scaling-stroke"><title>fewest steps: {}, {} steps</title></rect>"#,
+            x - r * 4.0,
+            y - r * 4.0,
+            r * 8.0,
+            r * 8.0,
+            fewest,
+            fewest.steps
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Draws small layouts the way the puzzle does: `-` and `|` for wires, `+` where a wire turns,
+/// `X` where different wires cross and `o` at the origin.
+pub(super) fn ascii<T: Coordinate>(wires: &Wires<T>) -> Result<String, Error> {
+    let ((x0, y0), (x1, y1)) = bounds(wires);
+    // Leave a border of empty cells, like the puzzle's drawings.
+    let (x0, y0, x1, y1) = (x0 - 1, y0 - 1, x1 + 1, y1 + 1);
+    if (x1 - x0 + 1).saturating_mul(y1 - y0 + 1) > ASCII_LIMIT {
+        return Err(Error::Unsupported(
+            "These wires are too big to draw as ascii art (try svg)".to_string(),
+        ));
+    }
+    let mut cells = SparseGrid::<(char, usize), i128>::new();
+    for (i, wire) in wires.wires.iter().enumerate() {
+        for (k, segment) in wire.iter().enumerate() {
+            let (start, end) = (wide(segment.start), wide(segment.end));
+            let delta = end - start;
+            let unit = Vector::new(delta.x.signum(), delta.y.signum());
+            let line = if segment.is_horizontal() { '-' } else { '|' };
+            let mut cell = start;
+            loop {
+                cell += unit;
+                let turns = cell == end && k + 1 < wire.len();
+                let mark = if turns { '+' } else { line };
+                let entry = cells.entry(cell).or_insert((mark, i));
+                *entry = match *entry {
+                    ('X', _) => ('X', i),
+                    (_, wire) if wire != i => ('X', i),
+                    (existing, _) if existing == mark => (mark, i),
+                    // A wire running over itself, or turning on its own line.
+                    _ => ('+', i),
+                };
+                if cell == end {
+                    break;
+                }
+            }
+        }
+    }
+    let mut ascii = String::new();
+    for y in (y0..=y1).rev() {
+        for x in x0..=x1 {
+            ascii.push(match cells.get(Point::new(x, y)) {
+                _ if (x, y) == (0, 0) => 'o',
+                Some((mark, _)) => *mark,
+                None => '.',
+            });
+        }
+        ascii.push('\n');
+    }
+    Ok(ascii)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse;
+    use super::*;
+    #[test]
+    fn puzzle() {
+        let wires = parse::<i32>("R8,U5,L5,D3\nU7,R6,D4,L4").unwrap();
+        let expected = "\
+...........
+.+-----+...
+.|.....|...
+.|..+--X-+.
+.|..|..|.|.
+.|.-X--+.|.
+.|..|....|.
+.|.......|.
+.o-------+.
+...........
+";
+        assert_eq!(ascii(&wires).unwrap(), expected);
+    }
+    #[test]
+    fn svg_marks() {
+        let wires = parse::<i32>("R8,U5,L5,D3\nU7,R6,D4,L4").unwrap();
+        let svg = svg(&wires, &wires.common());
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert!(svg.contains(r#"points="0,0 8,0 8,-5 3,-5 3,-2""#));
+        assert!(svg.contains("<title>closest: (3, 3) (wires 1 and 2), distance 6</title>"));
+        assert!(svg.contains("<title>fewest steps: (6, 5) (wires 1 and 2), 30 steps</title>"));
+    }
+}
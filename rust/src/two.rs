@@ -1,6 +1,7 @@
+use crate::{answer::Answer, error, params::Params, Part};
 use std::convert::{TryFrom, TryInto};
 
-static EMPTY: &'static [i32] = &[];
+static EMPTY: &[i32] = &[];
 
 enum Status {
     Running,
@@ -19,6 +20,17 @@ enum Error {
     UnknownOpcode(u8),
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::SignedOpcode => write!(f, "Negative opcode"),
+            Self::UnknownOpcode(opcode) => write!(f, "Unknown opcode: {}", opcode),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 impl From<TryFromError> for Error {
     fn from(err: TryFromError) -> Self {
         Self::UnknownOpcode(err.opcode)
@@ -110,17 +122,17 @@ impl TryFrom<u8> for Operation {
     }
 }
 
-fn part1(memory: &[i32]) -> String {
-    let mut program = memory.iter().copied().collect::<Vec<_>>();
-    program[1] = 12;
-    program[2] = 2;
+fn part1(memory: &[i32], noun: i32, verb: i32) -> Answer {
+    let mut program = memory.to_vec();
+    program[1] = noun;
+    program[2] = verb;
     let mut comp = Computer::new(program);
     let memory = comp.run().unwrap();
-    format!("{}", memory[0])
+    memory[0].into()
 }
 
-fn part2(memory: &[i32], target: i32) -> String {
-    let program = memory.iter().copied().collect::<Vec<_>>();
+fn part2(memory: &[i32], target: i32) -> Answer {
+    let program = memory.to_vec();
     for noun in 0..=99 {
         for verb in 0..=99 {
             let mut memory = program.clone();
@@ -129,25 +141,21 @@ fn part2(memory: &[i32], target: i32) -> String {
             let mut comp = Computer::new(memory);
             if let Ok(memory) = comp.run() {
                 if memory[0] == target {
-                    return format!("{}", 100 * noun + verb);
+                    return (100 * noun + verb).into();
                 }
             }
         }
     }
-    String::new()
-}
-
-pub(crate) fn run(input: String) -> [String; 2] {
-    let program = input
-        .lines()
-        .next()
-        .unwrap()
-        .split(',')
-        .filter_map(|x| str::parse::<i32>(x).ok())
-        .collect::<Vec<_>>();
-    let root = part1(&program);
-    let comb = part2(&program, 19_690_720);
-    [root, comb]
+    Answer::None(format!("no noun and verb give {}", target))
+}
+
+pub(crate) fn run(input: &str, part: Part, params: &Params) -> Result<Answer, error::Error> {
+    let line = error::first_line(input, "an Intcode program")?;
+    let program = error::numbers::<i32>(1, line, ',', "an integer")?;
+    Ok(match part {
+        Part::One => part1(&program, params.get_or("noun", 12)?, params.get_or("verb", 2)?),
+        Part::Two => part2(&program, params.get_or("target", 19_690_720)?),
+    })
 }
 
 #[cfg(test)]